@@ -0,0 +1,446 @@
+// Generates the `Config` accessors and the field table of `src/fields.rs` from the `vvenc_config`
+// members described by the vvenc-sys build script, so that every member of the linked VVenC is
+// covered.
+
+use std::{collections::HashSet, env, fmt::Write, fs, path::PathBuf};
+
+// Members with hand-written accessors, and the name of that accessor. Generated accessors avoid
+// every method of `Config` found in `src/`, so only members need listing here.
+const HANDWRITTEN: &[(&str, &str)] = &[
+    ("m_SourceWidth", "width"),
+    ("m_SourceHeight", "height"),
+    ("m_FrameRate", "framerate"),
+    ("m_FrameScale", "framerate"),
+    ("m_TicksPerSecond", "ticks_per_second"),
+    ("m_framesToBeEncoded", "frames_to_be_encoded"),
+    ("m_inputBitDepth", "input_bit_depth"),
+    ("m_outputBitDepth", "output_bit_depth"),
+    ("m_internalBitDepth", "internal_bit_depth"),
+    ("m_internChromaFormat", "internal_chroma_format"),
+    ("m_numThreads", "num_threads"),
+    ("m_QP", "qp"),
+    ("m_RCTargetBitrate", "target_bitrate"),
+    ("m_RCMaxBitrate", "rate_control"),
+    ("m_RCInitialQP", "rate_control"),
+    ("m_RCNumPasses", "num_passes"),
+    ("m_RCPass", "pass"),
+    ("m_verbosity", "log_level"),
+    ("m_profile", "profile"),
+    ("m_levelTier", "tier"),
+    ("m_level", "level"),
+    ("m_IntraPeriod", "intra_period"),
+    ("m_IntraPeriodSec", "intra_period_seconds"),
+    ("m_DecodingRefreshType", "decoding_refresh_type"),
+    ("m_GOPSize", "gop_size"),
+    ("m_HdrMode", "hdr_mode"),
+    ("m_SegmentMode", "segment_mode"),
+    ("m_usePerceptQPA", "use_percept_qpa"),
+    ("m_numTileCols", "num_tile_columns"),
+    ("m_numTileRows", "num_tile_rows"),
+    ("m_colourPrimaries", "colour_primaries"),
+    ("m_transferCharacteristics", "transfer_characteristics"),
+    ("m_matrixCoefficients", "matrix_coefficients"),
+    ("m_videoFullRangeFlag", "full_range"),
+    ("m_chromaSampleLocType", "chroma_sample_location"),
+    ("m_masteringDisplay", "mastering_display"),
+    ("m_contentLightLevel", "content_light_level"),
+    ("m_decodedPictureHashSEIType", "decoded_picture_hash"),
+    ("msgCtx", "logger"),
+    ("msgFnc", "logger"),
+];
+
+// Generated accessors named after the tool rather than after the member.
+const RENAMED: &[(&str, &str)] = &[
+    ("m_confWinLeft", "conformance_window_left"),
+    ("m_confWinRight", "conformance_window_right"),
+    ("m_confWinTop", "conformance_window_top"),
+    ("m_confWinBottom", "conformance_window_bottom"),
+    ("m_LookAhead", "lookahead"),
+    ("m_sliceTypeAdapt", "slice_type_adaptation"),
+    ("m_picReordering", "picture_reordering"),
+    ("m_entropyCodingSyncEnabled", "wpp"),
+    ("m_tileParallelCtuEnc", "tile_parallel_ctu_encoding"),
+    ("m_alfTempPred", "alf_temporal_prediction"),
+    ("m_bUseSAO", "sao"),
+    ("m_bLoopFilterDisable", "loop_filter_disabled"),
+    ("m_lumaReshapeEnable", "lmcs"),
+    ("m_IBCMode", "ibc"),
+    ("m_DepQuantEnabled", "dependent_quantization"),
+    ("m_SignDataHidingEnabled", "sign_data_hiding"),
+    ("m_TMVPModeId", "tmvp"),
+    ("m_SbTMVP", "sbtmvp"),
+    ("m_JointCbCrMode", "joint_cbcr"),
+    ("m_TS", "transform_skip"),
+    ("m_useBDPCM", "bdpcm"),
+    ("m_maxNumMergeCand", "max_num_merge_candidates"),
+    ("m_hrdParametersPresent", "hrd_parameters"),
+    ("m_bufferingPeriodSEIEnabled", "buffering_period_sei"),
+    ("m_pictureTimingSEIEnabled", "picture_timing_sei"),
+    ("m_cabacZeroWordPaddingEnabled", "cabac_zero_word_padding"),
+    (
+        "m_enablePictureHeaderInSliceHeader",
+        "picture_header_in_slice_header",
+    ),
+];
+
+// Integer members holding a flag or a mode, exposed through the types of `src/fields.rs`. Only
+// applied while the member is an `int`: VVenC has changed some of them between `bool` and `int`.
+const TYPED: &[(&str, &str)] = &[
+    ("m_AccessUnitDelimiter", "Option<bool>"),
+    ("m_vuiParametersPresent", "Option<bool>"),
+    ("m_hrdParametersPresent", "bool"),
+    ("m_bufferingPeriodSEIEnabled", "bool"),
+    ("m_pictureTimingSEIEnabled", "bool"),
+    ("m_LookAhead", "Option<bool>"),
+    ("m_sliceTypeAdapt", "Option<bool>"),
+    ("m_alfTempPred", "Option<bool>"),
+    ("m_usePerceptQPATempFiltISlice", "Option<bool>"),
+    ("m_vvencMCTF.MCTF", "ToolMode"),
+    ("m_TMVPModeId", "ToolMode"),
+    ("m_lumaReshapeEnable", "ScreenContentMode"),
+    ("m_IBCMode", "ScreenContentMode"),
+    ("m_TS", "ScreenContentMode"),
+    ("m_useBDPCM", "ScreenContentMode"),
+    ("m_RDOQ", "FastMode"),
+    ("m_MIP", "FastMode"),
+];
+
+const KEYWORDS: &[&str] = &[
+    "as", "box", "break", "const", "continue", "crate", "do", "dyn", "else", "enum", "extern",
+    "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move",
+    "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super",
+    "trait", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while",
+    "yield",
+];
+
+struct Member {
+    path: String,
+    declared: String,
+    ty: String,
+    doc: String,
+}
+
+enum Kind {
+    // Plain value, with its Rust type.
+    Value(String),
+    // Integer exposed through a typed enum.
+    Typed(&'static str),
+    // NUL-terminated string buffer.
+    Text,
+    // Pointers and structs without a meaningful value, only compared.
+    Opaque,
+}
+
+fn main() {
+    let table =
+        env::var("DEP_VVENC_CONFIG_FIELDS").expect("vvenc-sys describes the vvenc_config fields");
+    println!("cargo:rerun-if-changed={table}");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src");
+
+    let members: Vec<_> = fs::read_to_string(&table)
+        .expect("Couldn't read vvenc_config fields!")
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.splitn(4, '\t');
+            Some(Member {
+                path: columns.next()?.to_owned(),
+                declared: columns.next()?.to_owned(),
+                ty: columns.next()?.to_owned(),
+                doc: unescape(columns.next().unwrap_or_default()),
+            })
+        })
+        .collect();
+
+    let mut taken = config_methods();
+    for (member, name) in HANDWRITTEN {
+        assert!(
+            taken.contains(*name) || taken.contains(&format!("set_{name}")),
+            "HANDWRITTEN maps {member} to `{name}`, but Config has no such method"
+        );
+    }
+
+    let mut accessors = String::new();
+    let mut fields = String::new();
+    for member in &members {
+        let path = &member.path;
+        let kind = kind(member);
+        let handwritten = HANDWRITTEN
+            .iter()
+            .find(|(field, _)| *field == path.as_str())
+            .map(|(_, name)| name.to_string());
+        let accessor = match (&handwritten, &kind) {
+            (Some(_), _) => handwritten.clone(),
+            (None, Kind::Opaque) => None,
+            (None, _) => Some(accessor_name(path, &mut taken)),
+        };
+
+        if let (None, Some(name)) = (&handwritten, &accessor) {
+            write_accessor(&mut accessors, member, name, &kind);
+        }
+
+        let value = match &kind {
+            Kind::Value(_) => format!(
+                "Some((|c| get_raw(&c.{path}), |c, v| set_raw(&mut c.{path}, v, {path:?})))"
+            ),
+            Kind::Typed(ty) => format!(
+                "Some((|c| get_raw(&c.{path}), |c, v| set_typed::<{ty}>(&mut c.{path}, v, {path:?})))"
+            ),
+            Kind::Text => format!(
+                "Some((|c| get_text(&c.{path}), |c, v| set_text(&mut c.{path}, v, {path:?})))"
+            ),
+            Kind::Opaque => "None".to_owned(),
+        };
+        writeln!(
+            fields,
+            "    Field {{ member: {path:?}, accessor: {accessor:?}, eq: |a, b| a.{path} == b.{path}, value: {value} }},"
+        )
+        .unwrap();
+    }
+
+    let generated = format!(
+        "impl Config {{\n{accessors}}}\n\n\
+         // Every member of `vvenc_config`, in declaration order.\n\
+         #[allow(unknown_lints, unpredictable_function_pointer_comparisons)]\n\
+         pub(crate) static FIELDS: &[Field] = &[\n{fields}];\n"
+    );
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("config_fields.rs");
+    fs::write(out, generated).expect("Couldn't write Config accessors!");
+}
+
+fn kind(member: &Member) -> Kind {
+    if let Some((_, ty)) = TYPED.iter().find(|(field, _)| *field == member.path) {
+        if primitive(&member.ty) == Some("i32") {
+            return Kind::Typed(ty);
+        }
+    }
+    if let Some((element, _)) = array(&member.ty) {
+        if element.rsplit("::").next() == Some("c_char") {
+            return Kind::Text;
+        }
+    }
+    match value_type(&member.ty) {
+        Some(ty) => Kind::Value(ty),
+        None => Kind::Opaque,
+    }
+}
+
+fn value_type(ty: &str) -> Option<String> {
+    match array(ty) {
+        Some((element, len)) => Some(format!("[{}; {len}]", value_type(element)?)),
+        None => primitive(ty).map(str::to_owned),
+    }
+}
+
+fn primitive(ty: &str) -> Option<&'static str> {
+    Some(match ty.rsplit("::").next()? {
+        "bool" => "bool",
+        "c_int" | "i32" => "i32",
+        "c_uint" | "u32" => "u32",
+        "c_short" | "i16" => "i16",
+        "c_ushort" | "u16" => "u16",
+        "c_schar" | "i8" => "i8",
+        "c_uchar" | "u8" => "u8",
+        "c_longlong" | "i64" => "i64",
+        "c_ulonglong" | "u64" => "u64",
+        "c_float" | "f32" => "f32",
+        "c_double" | "f64" => "f64",
+        _ => return None,
+    })
+}
+
+fn array(ty: &str) -> Option<(&str, &str)> {
+    let inner = ty.strip_prefix('[')?.strip_suffix(']')?;
+    let (element, len) = inner.rsplit_once(';')?;
+    Some((element.trim(), len.trim()))
+}
+
+fn write_accessor(out: &mut String, member: &Member, name: &str, kind: &Kind) {
+    let path = &member.path;
+    let mut doc: Vec<String> = member
+        .doc
+        .lines()
+        .map(|line| line.trim().to_owned())
+        .collect();
+    if doc.iter().all(|line| line.is_empty()) {
+        doc = Vec::new();
+    } else {
+        doc.push(String::new());
+    }
+    let declared = match (kind, member.declared != member.ty) {
+        (Kind::Value(_), true) => format!(", a raw `{}`", member.declared),
+        _ => String::new(),
+    };
+    doc.push(format!("Maps to `vvenc_config::{path}`{declared}."));
+    for line in &doc {
+        writeln!(out, "    #[doc = {:?}]", format!(" {line}").trim_end()).unwrap();
+    }
+    let setter_doc = format!(" Sets [`Config::{name}`].");
+    match kind {
+        Kind::Value(ty) => write!(
+            out,
+            "    pub fn {name}(&self) -> {ty} {{ self.inner.{path} }}\n\
+             \x20   #[doc = {setter_doc:?}]\n\
+             \x20   pub fn set_{name}(&mut self, value: {ty}) -> &mut Self {{ self.inner.{path} = value; self }}\n"
+        ),
+        Kind::Typed(ty) => write!(
+            out,
+            "    pub fn {name}(&self) -> {ty} {{ TypedField::from_ffi(self.inner.{path}) }}\n\
+             \x20   #[doc = {setter_doc:?}]\n\
+             \x20   pub fn set_{name}(&mut self, value: {ty}) -> &mut Self {{ self.inner.{path} = value.to_ffi(); self }}\n"
+        ),
+        Kind::Text => write!(
+            out,
+            "    pub fn {name}(&self) -> String {{ text_from_ffi(&self.inner.{path}) }}\n\
+             \x20   #[doc = {setter_doc:?}]\n\
+             \x20   pub fn set_{name}(&mut self, value: &str) -> Result<&mut Self, Error> {{ text_to_ffi(&mut self.inner.{path}, value, {path:?})?; Ok(self) }}\n"
+        ),
+        Kind::Opaque => Ok(()),
+    }
+    .unwrap();
+}
+
+fn accessor_name(path: &str, taken: &mut HashSet<String>) -> String {
+    let mut name = match RENAMED.iter().find(|(field, _)| *field == path) {
+        Some((_, name)) => name.to_string(),
+        None => {
+            let mut parts = path.split('.').map(snake_case);
+            let mut name = parts.next().unwrap_or_default();
+            for part in parts {
+                // `m_vvencMCTF.MCTFSpeed` reads as `mctf_speed`.
+                let parent = name.trim_start_matches("vvenc_").to_owned();
+                name = if part.starts_with(&parent) {
+                    part
+                } else {
+                    format!("{parent}_{part}")
+                };
+            }
+            name
+        }
+    };
+    let collides = |name: &str, taken: &HashSet<String>| {
+        taken.contains(name) || taken.contains(&format!("set_{name}"))
+    };
+    if collides(&name, taken) {
+        println!(
+            "cargo:warning=accessor `{name}` of vvenc_config::{path} collides with a Config method \
+             and is generated as `{name}_field`; add the member to HANDWRITTEN or RENAMED"
+        );
+    }
+    if KEYWORDS.contains(&name.as_str()) || collides(&name, taken) {
+        name.push_str("_field");
+    }
+    while collides(&name, taken) {
+        name.push('_');
+    }
+    taken.insert(name.clone());
+    taken.insert(format!("set_{name}"));
+    name
+}
+
+// `m_bUseSAO` becomes `use_sao`, `m_maxMTTDepth` becomes `max_mtt_depth`.
+fn snake_case(member: &str) -> String {
+    let member = member.strip_prefix("m_").unwrap_or(member);
+    let member = match member.strip_prefix('b') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_uppercase()) => rest,
+        _ => member,
+    };
+    let chars: Vec<char> = member.chars().collect();
+    let mut name = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_ascii_lowercase());
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_lower)
+            {
+                name.push('_');
+            }
+        }
+        if c != '_' || !name.ends_with('_') {
+            name.push(c.to_ascii_lowercase());
+        }
+    }
+    name
+}
+
+fn unescape(doc: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = doc.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+// Names of the methods in the `impl Config` blocks of `src/`, including trait implementations.
+fn config_methods() -> HashSet<String> {
+    let mut methods = HashSet::new();
+    for entry in fs::read_dir("src").expect("Couldn't read src!") {
+        let path = entry.expect("Couldn't read src!").path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("rs") {
+            continue;
+        }
+        let source = fs::read_to_string(&path).expect("Couldn't read source file!");
+        let mut rest = source.as_str();
+        while let Some(start) = rest.find(" Config {") {
+            let header_start = rest[..start].rfind('\n').map_or(0, |line| line + 1);
+            let header = rest[header_start..start].trim_start();
+            let body = &rest[start + " Config {".len()..];
+            let end = closing_brace(body);
+            if header.starts_with("impl") {
+                for item in body[..end].split("fn ").skip(1) {
+                    let name: String = item
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    if !name.is_empty() {
+                        methods.insert(name);
+                    }
+                }
+            }
+            rest = &body[end..];
+        }
+    }
+    methods
+}
+
+// Position of the brace closing the block whose body starts `text`, skipping comments, string
+// literals and character literals.
+fn closing_brace(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i += text[i..].find('\n').unwrap_or(text.len() - i);
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            // Character literals such as '{' or '\'', not lifetimes.
+            b'\'' if bytes.get(i + 1) == Some(&b'\\') => i += 3,
+            b'\'' if bytes.get(i + 2) == Some(&b'\'') => i += 2,
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return i,
+            b'}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    text.len()
+}
@@ -0,0 +1,281 @@
+use std::{fmt::Write, path::Path};
+
+use vvenc_sys::vvenc_get_config_as_string;
+
+use crate::{
+    string_from_ptr, ChromaFormat, Config, DecodingRefreshType, Error, HdrMode, Level, LogLevel,
+    Profile, SegmentMode, Tier,
+};
+
+// VVenC configuration files use `Key : value` lines with `#` comments. Keys and values are the
+// ones understood by vvencapp, so parsing is delegated to `vvenc_set_param`. Members of
+// `vvenc_config` without a vvencapp option are written separately by `to_member_cfg_string` as
+// `m_member : value` lines, which only this crate reads.
+impl Config {
+    pub fn from_cfg_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_cfg_str(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_cfg_str(cfg: &str) -> Result<Self, Error> {
+        let mut config = Config::default();
+        config.apply_cfg_str(cfg)?;
+        Ok(config)
+    }
+
+    pub fn apply_cfg_str(&mut self, cfg: &str) -> Result<&mut Self, Error> {
+        for line in cfg.lines() {
+            let line = line.split_once('#').map_or(line, |(line, _)| line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(Error::parameter(format!("malformed cfg line {line:?}")));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if key.starts_with("m_") {
+                self.set_field_str(key, value)?;
+            } else {
+                self.set_param(key, value)?;
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn to_cfg_string(&self) -> String {
+        let framerate = self.framerate();
+        let [input_bit_depth, input_bit_depth_chroma] = self.input_bit_depth();
+        let [output_bit_depth, output_bit_depth_chroma] = self.output_bit_depth();
+        let [internal_bit_depth, _] = self.internal_bit_depth();
+
+        let entries: &[(&str, String)] = &[
+            ("SourceWidth", self.width().to_string()),
+            ("SourceHeight", self.height().to_string()),
+            ("FrameRate", framerate.num.to_string()),
+            ("FrameScale", framerate.den.to_string()),
+            ("TicksPerSecond", self.ticks_per_second().to_string()),
+            ("FramesToBeEncoded", self.frames_to_be_encoded().to_string()),
+            ("InputBitDepth", input_bit_depth.to_string()),
+            ("InputBitDepthC", input_bit_depth_chroma.to_string()),
+            ("OutputBitDepth", output_bit_depth.to_string()),
+            ("OutputBitDepthC", output_bit_depth_chroma.to_string()),
+            ("InternalBitDepth", internal_bit_depth.to_string()),
+            (
+                "InternalChromaFormat",
+                chroma_format_value(self.internal_chroma_format()).to_string(),
+            ),
+            ("Threads", self.num_threads().to_string()),
+            ("QP", self.qp().0.to_string()),
+            ("TargetBitrate", self.target_bitrate().to_string()),
+            ("Passes", self.num_passes().to_string()),
+            ("Pass", self.pass().to_string()),
+            ("Profile", profile_value(self.profile()).to_string()),
+            ("Tier", tier_value(self.tier()).to_string()),
+            ("Level", level_value(self.level()).to_string()),
+            ("IntraPeriod", self.intra_period().to_string()),
+            ("RefreshSec", self.intra_period_seconds().to_string()),
+            (
+                "DecodingRefreshType",
+                decoding_refresh_type_value(self.decoding_refresh_type()).to_string(),
+            ),
+            ("GOPSize", self.gop_size().to_string()),
+            ("HdrMode", hdr_mode_value(self.hdr_mode()).to_string()),
+            (
+                "Segment",
+                segment_mode_value(self.segment_mode()).to_string(),
+            ),
+            ("PerceptQPA", u8::from(self.use_percept_qpa()).to_string()),
+            (
+                "Tiles",
+                format!("{}x{}", self.num_tile_columns(), self.num_tile_rows()),
+            ),
+            ("Verbosity", (self.log_level() as u8).to_string()),
+        ];
+
+        let mut cfg = String::new();
+        for (key, value) in entries {
+            let _ = writeln!(cfg, "{key:<24}: {value}");
+        }
+        if let Some(mastering_display) = self.mastering_display() {
+            let _ = writeln!(
+                cfg,
+                "{:<24}: {mastering_display}",
+                "MasteringDisplayColourVolume"
+            );
+        }
+
+        let mut core = Config::default();
+        core.apply_cfg_str(&cfg)
+            .expect("the vvencapp keys written by to_cfg_string are accepted by apply_cfg_str");
+        for (member, value) in self.changed_fields(&core) {
+            if let Some((_, key)) = CFG_KEYS.iter().find(|(field, _)| *field == member) {
+                let _ = writeln!(cfg, "{key:<24}: {value}");
+            }
+        }
+        cfg
+    }
+
+    /// Members that [`Config::to_cfg_string`] cannot express because vvencapp has no option for
+    /// them, as `m_member : value` lines. Only [`Config::apply_cfg_str`] reads them, so they are
+    /// kept out of the vvencapp profile and applied after it.
+    pub fn to_member_cfg_string(&self) -> String {
+        let mut profile = Config::default();
+        profile
+            .apply_cfg_str(&self.to_cfg_string())
+            .expect("the output of to_cfg_string is accepted by apply_cfg_str");
+        let mut cfg = String::new();
+        for (member, value) in self.changed_fields(&profile) {
+            let _ = writeln!(cfg, "{member:<24}: {value}");
+        }
+        cfg
+    }
+
+    /// Human-readable description of the configuration as printed by VVenC, not meant to be parsed.
+    pub fn summary(&self, level: LogLevel) -> String {
+        let mut config = self.clone();
+        unsafe {
+            string_from_ptr(vvenc_get_config_as_string(
+                &mut config.inner,
+                level.to_ffi(),
+            ))
+        }
+        .unwrap_or_default()
+    }
+}
+
+// vvencapp options of the members that `to_cfg_string` writes when they differ from what its core
+// keys imply. Their values are the raw member values, which vvencapp parses as well.
+const CFG_KEYS: &[(&str, &str)] = &[
+    ("m_RCMaxBitrate", "MaxBitrate"),
+    ("m_LookAhead", "LookAhead"),
+    ("m_sliceTypeAdapt", "STA"),
+    ("m_vvencMCTF.MCTF", "MCTF"),
+    ("m_vvencMCTF.MCTFSpeed", "MCTFSpeed"),
+    ("m_vvencMCTF.MCTFFutureReference", "MCTFFutureReference"),
+    ("m_usePerceptQPATempFiltISlice", "PerceptQPATempFiltIPic"),
+    ("m_SearchRange", "SearchRange"),
+    ("m_maxNumMergeCand", "MaxNumMergeCand"),
+    ("m_RDOQ", "RDOQ"),
+    ("m_DepQuantEnabled", "DepQuant"),
+    ("m_SignDataHidingEnabled", "SignHideFlag"),
+    ("m_bUseSAO", "SAO"),
+    ("m_alf", "ALF"),
+    ("m_ccalf", "CCALF"),
+    ("m_alfTempPred", "ALFTempPred"),
+    ("m_lumaReshapeEnable", "LMCSEnable"),
+    ("m_IBCMode", "IBC"),
+    ("m_TS", "TransformSkip"),
+    ("m_useBDPCM", "BDPCM"),
+    ("m_MIP", "MIP"),
+    ("m_TMVPModeId", "TMVPMode"),
+    ("m_SbTMVP", "SbTMVP"),
+    ("m_JointCbCrMode", "JointCbCr"),
+    ("m_entropyCodingSyncEnabled", "WaveFrontSynchro"),
+    ("m_confWinLeft", "ConfWinLeft"),
+    ("m_confWinRight", "ConfWinRight"),
+    ("m_confWinTop", "ConfWinTop"),
+    ("m_confWinBottom", "ConfWinBottom"),
+    ("m_AccessUnitDelimiter", "AccessUnitDelimiter"),
+    ("m_vuiParametersPresent", "VuiParametersPresent"),
+    ("m_hrdParametersPresent", "HrdParametersPresent"),
+    ("m_bufferingPeriodSEIEnabled", "SEIBufferingPeriod"),
+    ("m_pictureTimingSEIEnabled", "SEIPictureTiming"),
+    ("m_decodedPictureHashSEIType", "SEIDecodedPictureHash"),
+    ("m_colourDescriptionPresent", "ColourDescriptionPresent"),
+    ("m_colourPrimaries", "ColourPrimaries"),
+    ("m_transferCharacteristics", "TransferCharacteristics"),
+    ("m_matrixCoefficients", "MatrixCoefficients"),
+    ("m_videoSignalTypePresent", "VideoSignalTypePresent"),
+    ("m_videoFullRangeFlag", "VideoFullRange"),
+    ("m_chromaLocInfoPresent", "ChromaLocInfoPresent"),
+    ("m_chromaSampleLocType", "ChromaSampleLocType"),
+    ("m_contentLightLevel", "MaxContentLightLevel"),
+    ("m_traceFile", "TraceFile"),
+    ("m_traceRule", "TraceRule"),
+];
+
+fn chroma_format_value(chroma_format: ChromaFormat) -> &'static str {
+    match chroma_format {
+        ChromaFormat::Chroma400 => "400",
+        ChromaFormat::Chroma420 => "420",
+        ChromaFormat::Chroma422 => "422",
+        ChromaFormat::Chroma444 => "444",
+        ChromaFormat::Unknown(_) => "0",
+    }
+}
+
+fn profile_value(profile: Profile) -> &'static str {
+    match profile {
+        Profile::Auto => "auto",
+        Profile::Main10 => "main_10",
+        Profile::Main10StillPicture => "main_10_still_picture",
+        Profile::Main10444 => "main_10_444",
+        Profile::Main10444StillPicture => "main_10_444_still_picture",
+        Profile::MultilayerMain10 => "multilayer_main_10",
+        Profile::MultilayerMain10StillPicture => "multilayer_main_10_still_picture",
+        Profile::MultilayerMain10444 => "multilayer_main_10_444",
+        Profile::MultilayerMain10444StillPicture => "multilayer_main_10_444_still_picture",
+    }
+}
+
+fn tier_value(tier: Tier) -> &'static str {
+    match tier {
+        Tier::Main => "main",
+        Tier::High => "high",
+    }
+}
+
+fn level_value(level: Level) -> &'static str {
+    match level {
+        Level::Auto => "auto",
+        Level::Level1 => "1",
+        Level::Level2 => "2",
+        Level::Level2_1 => "2.1",
+        Level::Level3 => "3",
+        Level::Level3_1 => "3.1",
+        Level::Level4 => "4",
+        Level::Level4_1 => "4.1",
+        Level::Level5 => "5",
+        Level::Level5_1 => "5.1",
+        Level::Level5_2 => "5.2",
+        Level::Level6 => "6",
+        Level::Level6_1 => "6.1",
+        Level::Level6_2 => "6.2",
+        Level::Level6_3 => "6.3",
+        Level::Level15_5 => "15.5",
+    }
+}
+
+fn decoding_refresh_type_value(decoding_refresh_type: DecodingRefreshType) -> &'static str {
+    match decoding_refresh_type {
+        DecodingRefreshType::None => "none",
+        DecodingRefreshType::Cra => "cra",
+        DecodingRefreshType::Idr => "idr",
+        DecodingRefreshType::RecoveryPointSei => "rpsei",
+        DecodingRefreshType::Idr2 => "idr2",
+        DecodingRefreshType::CraCre => "cra_cre",
+        DecodingRefreshType::IdrNoRadl => "idr_no_radl",
+    }
+}
+
+fn hdr_mode_value(hdr_mode: HdrMode) -> &'static str {
+    match hdr_mode {
+        HdrMode::Off => "off",
+        HdrMode::Pq => "pq",
+        HdrMode::Hlg => "hlg",
+        HdrMode::PqBt2020 => "pq_2020",
+        HdrMode::HlgBt2020 => "hlg_2020",
+        HdrMode::UserDefined => "user",
+        HdrMode::SdrBt709 => "sdr_709",
+        HdrMode::SdrBt2020 => "sdr_2020",
+        HdrMode::SdrBt470bg => "sdr_470bg",
+    }
+}
+
+fn segment_mode_value(segment_mode: SegmentMode) -> &'static str {
+    match segment_mode {
+        SegmentMode::Off => "off",
+        SegmentMode::First => "first",
+        SegmentMode::Mid => "mid",
+        SegmentMode::Last => "last",
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    fields::FieldValue, ChromaFormat, ChromaSampleLocation, ColourPrimaries, Config,
+    ContentLightLevel, DecodingRefreshType, Error, HdrMode, Level, LogLevel, MasteringDisplay,
+    MatrixCoefficients, PictureHash, Profile, Qp, RateControl, Rational, SegmentMode, Tier,
+    TransferCharacteristics,
+};
+
+// Lets the typed fields use both plain and fallible setters.
+trait SetterResult {
+    fn into_result(self) -> Result<(), Error>;
+}
+
+impl SetterResult for &mut Config {
+    fn into_result(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl SetterResult for Result<&mut Config, Error> {
+    fn into_result(self) -> Result<(), Error> {
+        self.map(|_| ())
+    }
+}
+
+// Only the fields diverging from `vvenc_config_default` are serialized: the ones with a typed
+// accessor listed below under its name, and any other `vvenc_config` member left different by them
+// under `fields`, keyed by member name. Deserialization starts from the defaults, applies the
+// typed fields, then the members, and validates the result.
+macro_rules! config_repr {
+    ($($field:ident: $ty:ty => $setter:ident),* $(,)?) => {
+        #[derive(Default, Clone, Serialize, Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        struct ConfigRepr {
+            $(
+                #[serde(skip_serializing_if = "Option::is_none")]
+                $field: Option<$ty>,
+            )*
+            #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+            fields: BTreeMap<String, FieldValue>,
+        }
+
+        impl ConfigRepr {
+            fn from_config(config: &Config) -> Result<Self, Error> {
+                let default = Config::default();
+                let mut repr = Self {
+                    $($field: (config.$field() != default.$field()).then(|| config.$field()),)*
+                    fields: BTreeMap::new(),
+                };
+                let mut typed = Config::default();
+                repr.clone().apply(&mut typed)?;
+                repr.fields = config
+                    .changed_fields(&typed)
+                    .map(|(member, value)| (member.to_owned(), value))
+                    .collect();
+                Ok(repr)
+            }
+
+            fn apply(self, config: &mut Config) -> Result<(), Error> {
+                $(
+                    if let Some(value) = self.$field {
+                        config.$setter(value).into_result()?;
+                    }
+                )*
+                for (member, value) in &self.fields {
+                    config.set_field(member, value)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+config_repr! {
+    width: i32 => set_width,
+    height: i32 => set_height,
+    framerate: Rational => set_framerate,
+    ticks_per_second: i32 => set_ticks_per_second,
+    frames_to_be_encoded: i32 => set_frames_to_be_encoded,
+    input_bit_depth: [i32; 2] => set_input_bit_depth,
+    output_bit_depth: [i32; 2] => set_output_bit_depth,
+    num_threads: i32 => set_num_threads,
+    rate_control: RateControl => set_rate_control,
+    qp: Qp => set_qp,
+    profile: Profile => set_profile,
+    tier: Tier => set_tier,
+    level: Level => set_level,
+    intra_period: i32 => set_intra_period,
+    intra_period_seconds: i32 => set_intra_period_seconds,
+    decoding_refresh_type: DecodingRefreshType => set_decoding_refresh_type,
+    gop_size: i32 => set_gop_size,
+    pass: i32 => set_pass,
+    internal_bit_depth: [i32; 2] => set_internal_bit_depth,
+    hdr_mode: HdrMode => set_hdr_mode,
+    colour_primaries: ColourPrimaries => set_colour_primaries,
+    transfer_characteristics: TransferCharacteristics => set_transfer_characteristics,
+    matrix_coefficients: MatrixCoefficients => set_matrix_coefficients,
+    full_range: bool => set_full_range,
+    chroma_sample_location: ChromaSampleLocation => set_chroma_sample_location,
+    mastering_display: Option<MasteringDisplay> => set_mastering_display,
+    content_light_level: Option<ContentLightLevel> => set_content_light_level,
+    decoded_picture_hash: PictureHash => set_decoded_picture_hash,
+    segment_mode: SegmentMode => set_segment_mode,
+    use_percept_qpa: bool => set_use_percept_qpa,
+    num_tile_columns: i32 => set_num_tile_columns,
+    num_tile_rows: i32 => set_num_tile_rows,
+    internal_chroma_format: ChromaFormat => set_internal_chroma_format,
+    log_level: LogLevel => set_log_level,
+}
+
+impl Serialize for Config {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ConfigRepr::from_config(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut config = Config::default();
+        ConfigRepr::deserialize(deserializer)?
+            .apply(&mut config)
+            .map_err(de::Error::custom)?;
+        match config.validate() {
+            Ok(config) => Ok(config.into_inner()),
+            Err(diagnostics) => {
+                let messages: Vec<_> = diagnostics
+                    .into_iter()
+                    .map(|diagnostic| diagnostic.message)
+                    .collect();
+                Err(de::Error::custom(format!(
+                    "invalid configuration: {}",
+                    messages.join("; ")
+                )))
+            }
+        }
+    }
+}
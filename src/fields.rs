@@ -0,0 +1,433 @@
+use std::{
+    ffi::{c_char, c_int},
+    fmt,
+};
+
+use vvenc_sys::vvenc_config;
+
+use crate::{Config, ConfigDiagnostic, Error, LogLevel};
+
+// Accessors for the `vvenc_config` members without a hand-written one, and the `FIELDS` table,
+// generated by build.rs.
+include!(concat!(env!("OUT_DIR"), "/config_fields.rs"));
+
+/// Mode of a coding tool that VVenC can also enable on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ToolMode {
+    Off,
+    On,
+    Automatic,
+}
+
+/// Mode of a coding tool aimed at screen content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ScreenContentMode {
+    Off,
+    On,
+    /// Only enabled when the input is detected as screen content.
+    ScreenContent,
+}
+
+/// Mode of a coding tool with a faster, less thorough variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FastMode {
+    Off,
+    On,
+    Fast,
+}
+
+// Integer member exposed through a typed value. Out of range values, which VVenC rejects when
+// opening the encoder, read as the closest mode rather than failing.
+pub(crate) trait TypedField: Sized {
+    fn to_ffi(self) -> c_int;
+    fn try_from_ffi(value: c_int) -> Option<Self>;
+    fn from_ffi(value: c_int) -> Self;
+}
+
+impl TypedField for bool {
+    fn to_ffi(self) -> c_int {
+        self.into()
+    }
+
+    fn try_from_ffi(value: c_int) -> Option<Self> {
+        match value {
+            0 | 1 => Some(value != 0),
+            _ => None,
+        }
+    }
+
+    fn from_ffi(value: c_int) -> Self {
+        value != 0
+    }
+}
+
+// `None` stands for VVenC's automatic choice, stored as -1.
+impl TypedField for Option<bool> {
+    fn to_ffi(self) -> c_int {
+        self.map_or(-1, c_int::from)
+    }
+
+    fn try_from_ffi(value: c_int) -> Option<Self> {
+        match value {
+            -1 => Some(None),
+            0 | 1 => Some(Some(value != 0)),
+            _ => None,
+        }
+    }
+
+    fn from_ffi(value: c_int) -> Self {
+        (value >= 0).then_some(value != 0)
+    }
+}
+
+macro_rules! typed_modes {
+    ($($ty:ident { $($variant:ident = $value:literal),* })*) => {
+        $(
+            impl TypedField for $ty {
+                fn to_ffi(self) -> c_int {
+                    match self {
+                        $(Self::$variant => $value,)*
+                    }
+                }
+
+                fn try_from_ffi(value: c_int) -> Option<Self> {
+                    match value {
+                        $($value => Some(Self::$variant),)*
+                        _ => None,
+                    }
+                }
+
+                fn from_ffi(value: c_int) -> Self {
+                    Self::try_from_ffi(value.clamp(0, 2)).unwrap_or(Self::Off)
+                }
+            }
+        )*
+    };
+}
+
+typed_modes! {
+    ToolMode { Off = 0, On = 1, Automatic = 2 }
+    ScreenContentMode { Off = 0, On = 1, ScreenContent = 2 }
+    FastMode { Off = 0, On = 1, Fast = 2 }
+}
+
+// Members that vvenc_reconfig applies to a running encoder.
+const RUNTIME_FIELDS: &[&str] = &["m_QP", "m_RCTargetBitrate", "m_RCMaxBitrate"];
+
+// Member of `vvenc_config`, as listed in the generated `FIELDS` table.
+pub(crate) struct Field {
+    // Member name, nested structs being flattened as `m_outer.inner`.
+    pub member: &'static str,
+    // `Config` accessor covering the member, if any.
+    pub accessor: Option<&'static str>,
+    pub eq: fn(&vvenc_config, &vvenc_config) -> bool,
+    // Reads and writes the member, unless it holds a pointer or a struct.
+    pub value: Option<(GetField, SetField)>,
+}
+
+type GetField = fn(&vvenc_config) -> FieldValue;
+type SetField = fn(&mut vvenc_config, &FieldValue) -> Result<(), Error>;
+
+// Untyped value of a member, used to serialize the members without an accessor.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub(crate) enum FieldValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Array(Vec<FieldValue>),
+}
+
+impl FieldValue {
+    // Parses `s` as a value of the same shape as `self`, arrays being comma-separated.
+    fn parse_like(&self, s: &str) -> Option<FieldValue> {
+        if let FieldValue::Text(_) = self {
+            return Some(FieldValue::Text(s.to_owned()));
+        }
+        let mut items = s.split(',').map(str::trim);
+        let value = self.parse_items(&mut items)?;
+        items.next().is_none().then_some(value)
+    }
+
+    fn parse_items<'a>(&self, items: &mut impl Iterator<Item = &'a str>) -> Option<FieldValue> {
+        Some(match self {
+            FieldValue::Bool(_) => FieldValue::Bool(match items.next()? {
+                "1" | "true" => true,
+                "0" | "false" => false,
+                _ => return None,
+            }),
+            FieldValue::Int(_) => FieldValue::Int(items.next()?.parse().ok()?),
+            FieldValue::Float(_) => FieldValue::Float(items.next()?.parse().ok()?),
+            FieldValue::Text(_) => FieldValue::Text(items.next()?.to_owned()),
+            FieldValue::Array(elements) => FieldValue::Array(
+                elements
+                    .iter()
+                    .map(|element| element.parse_items(items))
+                    .collect::<Option<_>>()?,
+            ),
+        })
+    }
+}
+
+// Arrays are written comma-separated and flags as 1 or 0, as `FieldValue::parse_like` reads them
+// back.
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Bool(value) => write!(f, "{}", u8::from(*value)),
+            FieldValue::Int(value) => write!(f, "{value}"),
+            FieldValue::Float(value) => write!(f, "{value}"),
+            FieldValue::Text(value) => f.write_str(value),
+            FieldValue::Array(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Conversion between a member and its untyped value.
+trait RawValue: Sized {
+    fn to_value(&self) -> FieldValue;
+    fn from_value(value: &FieldValue) -> Option<Self>;
+}
+
+impl RawValue for bool {
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Bool(*self)
+    }
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Bool(value) => Some(*value),
+            FieldValue::Int(0) => Some(false),
+            FieldValue::Int(1) => Some(true),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! raw_values {
+    (int: $($int:ty),*; float: $($float:ty),*) => {
+        $(
+            impl RawValue for $int {
+                fn to_value(&self) -> FieldValue {
+                    FieldValue::Int(*self as i64)
+                }
+
+                fn from_value(value: &FieldValue) -> Option<Self> {
+                    match value {
+                        FieldValue::Int(value) => (*value).try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+        $(
+            impl RawValue for $float {
+                fn to_value(&self) -> FieldValue {
+                    FieldValue::Float(*self as f64)
+                }
+
+                fn from_value(value: &FieldValue) -> Option<Self> {
+                    match value {
+                        FieldValue::Float(value) => Some(*value as $float),
+                        FieldValue::Int(value) => Some(*value as $float),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+raw_values!(int: i8, u8, i16, u16, i32, u32, i64, u64; float: f32, f64);
+
+impl<T: RawValue, const N: usize> RawValue for [T; N] {
+    fn to_value(&self) -> FieldValue {
+        FieldValue::Array(self.iter().map(RawValue::to_value).collect())
+    }
+
+    fn from_value(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Array(elements) => elements
+                .iter()
+                .map(T::from_value)
+                .collect::<Option<Vec<_>>>()?
+                .try_into()
+                .ok(),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_value(member: &str, value: &FieldValue) -> Error {
+    Error::InvalidParameterValue {
+        name: member.to_owned(),
+        value: value.to_string(),
+    }
+}
+
+fn get_raw<T: RawValue>(field: &T) -> FieldValue {
+    field.to_value()
+}
+
+fn set_raw<T: RawValue>(field: &mut T, value: &FieldValue, member: &str) -> Result<(), Error> {
+    *field = T::from_value(value).ok_or_else(|| invalid_value(member, value))?;
+    Ok(())
+}
+
+fn set_typed<T: TypedField>(
+    field: &mut c_int,
+    value: &FieldValue,
+    member: &str,
+) -> Result<(), Error> {
+    match c_int::from_value(value).and_then(T::try_from_ffi) {
+        Some(typed) => {
+            *field = typed.to_ffi();
+            Ok(())
+        }
+        None => Err(invalid_value(member, value)),
+    }
+}
+
+fn get_text(field: &[c_char]) -> FieldValue {
+    FieldValue::Text(text_from_ffi(field))
+}
+
+fn set_text(field: &mut [c_char], value: &FieldValue, member: &str) -> Result<(), Error> {
+    match value {
+        FieldValue::Text(text) => text_to_ffi(field, text, member),
+        _ => Err(invalid_value(member, value)),
+    }
+}
+
+fn field_value(member: &str) -> Result<(GetField, SetField), Error> {
+    FIELDS
+        .iter()
+        .find(|field| field.member == member)
+        .and_then(|field| field.value)
+        .ok_or_else(|| Error::UnknownParameter(member.to_owned()))
+}
+
+fn text_from_ffi(field: &[c_char]) -> String {
+    let bytes: Vec<u8> = field
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// Fails if `text` does not fit with its NUL terminator, or contains a NUL byte.
+fn text_to_ffi(field: &mut [c_char], text: &str, member: &str) -> Result<(), Error> {
+    if text.len() >= field.len() || text.contains('\0') {
+        return Err(Error::InvalidParameterValue {
+            name: member.to_owned(),
+            value: text.to_owned(),
+        });
+    }
+    field.fill(0);
+    for (dst, &src) in field.iter_mut().zip(text.as_bytes()) {
+        *dst = src as c_char;
+    }
+    Ok(())
+}
+
+impl Config {
+    // Members whose value differs from `base`, with their value in `self`. Members without a value
+    // are skipped.
+    pub(crate) fn changed_fields<'a>(
+        &'a self,
+        base: &'a Config,
+    ) -> impl Iterator<Item = (&'static str, FieldValue)> + 'a {
+        FIELDS.iter().filter_map(|field| {
+            let (get, _) = field.value?;
+            (!(field.eq)(&self.inner, &base.inner)).then(|| (field.member, get(&self.inner)))
+        })
+    }
+
+    // Sets a member by name, e.g. `m_LookAhead` or `m_vvencMCTF.MCTFSpeed`.
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_field(&mut self, member: &str, value: &FieldValue) -> Result<(), Error> {
+        let (_, set) = field_value(member)?;
+        let previous = self.inner;
+        set(&mut self.inner, value)?;
+        self.check_typed_members(previous, member, &value.to_string())
+    }
+
+    // Sets a member from its cfg file representation.
+    pub(crate) fn set_field_str(&mut self, member: &str, value: &str) -> Result<(), Error> {
+        let (get, set) = field_value(member)?;
+        let parsed =
+            get(&self.inner)
+                .parse_like(value)
+                .ok_or_else(|| Error::InvalidParameterValue {
+                    name: member.to_owned(),
+                    value: value.to_owned(),
+                })?;
+        let previous = self.inner;
+        set(&mut self.inner, &parsed)?;
+        self.check_typed_members(previous, member, value)
+    }
+
+    // Members changed from `other` that VVenC can only apply when opening the encoder, named after
+    // their accessor. vvenc_reconfig is limited to rate control, and the encoder keeps calling the
+    // logger it was opened with.
+    pub(crate) fn static_changes(&self, other: &Config) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        for field in FIELDS {
+            if RUNTIME_FIELDS.contains(&field.member) || (field.eq)(&self.inner, &other.inner) {
+                continue;
+            }
+            let name = field.accessor.unwrap_or(field.member);
+            if !changes.contains(&name) {
+                changes.push(name);
+            }
+        }
+        changes
+    }
+
+    // HRD signalling depends on rate control, and the buffering period and picture timing SEI
+    // messages depend on the HRD parameters.
+    pub(crate) fn hrd_diagnostics(&self) -> Vec<ConfigDiagnostic> {
+        let mut diagnostics = Vec::new();
+        if (self.buffering_period_sei() || self.picture_timing_sei()) && !self.hrd_parameters() {
+            diagnostics.push(ConfigDiagnostic {
+                severity: LogLevel::Error,
+                message: "buffering period and picture timing SEI require HRD parameters".into(),
+                field: Some("hrd_parameters"),
+            });
+        }
+        if self.hrd_parameters() && self.target_bitrate() <= 0 {
+            diagnostics.push(ConfigDiagnostic {
+                severity: LogLevel::Error,
+                message: "HRD parameters require a target bitrate".into(),
+                field: Some("target_bitrate"),
+            });
+        }
+        diagnostics
+    }
+
+    /// Names of the `vvenc_config` members without an accessor, such as pointers and nested
+    /// structs. Members of nested structs are named `m_outer.inner`.
+    pub fn unwrapped_fields() -> impl Iterator<Item = &'static str> {
+        FIELDS
+            .iter()
+            .filter(|field| field.accessor.is_none())
+            .map(|field| field.member)
+    }
+}
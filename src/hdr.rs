@@ -0,0 +1,300 @@
+use std::{fmt, str::FromStr};
+
+use crate::{Config, ConfigDiagnostic, Error, HdrMode, LogLevel};
+
+// SEI chromaticities are coded in increments of 0.00002 and luminances in 0.0001 cd/m².
+const CHROMATICITY_SCALE: f64 = 50000.0;
+const LUMINANCE_SCALE: f64 = 10000.0;
+
+/// CIE 1931 xy chromaticity coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chromaticity {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Chromaticity {
+    fn is_valid(self) -> bool {
+        (0.0..=1.0).contains(&self.x) && (0.0..=1.0).contains(&self.y)
+    }
+
+    fn to_ffi(self) -> [u32; 2] {
+        [
+            (self.x * CHROMATICITY_SCALE).round() as u32,
+            (self.y * CHROMATICITY_SCALE).round() as u32,
+        ]
+    }
+
+    fn from_ffi([x, y]: [u32; 2]) -> Self {
+        Self {
+            x: x as f64 / CHROMATICITY_SCALE,
+            y: y as f64 / CHROMATICITY_SCALE,
+        }
+    }
+}
+
+/// SMPTE ST 2086 mastering display colour volume. Luminances are in cd/m².
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MasteringDisplay {
+    /// Red, green and blue primaries, in that order.
+    pub primaries: [Chromaticity; 3],
+    pub white_point: Chromaticity,
+    pub max_luminance: f64,
+    pub min_luminance: f64,
+}
+
+impl MasteringDisplay {
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(chromaticity) = self
+            .primaries
+            .iter()
+            .chain([&self.white_point])
+            .find(|chromaticity| !chromaticity.is_valid())
+        {
+            return Err(Error::parameter(format!(
+                "chromaticity {chromaticity:?} is out of range [0, 1]"
+            )));
+        }
+        let max_coded = u32::MAX as f64 / LUMINANCE_SCALE;
+        if !(0.0..=max_coded).contains(&self.min_luminance)
+            || !(0.0..=max_coded).contains(&self.max_luminance)
+        {
+            return Err(Error::parameter(format!(
+                "mastering display luminance range [{}, {}] is out of range",
+                self.min_luminance, self.max_luminance
+            )));
+        }
+        if self.max_luminance <= self.min_luminance {
+            return Err(Error::parameter(format!(
+                "mastering display maximum luminance {} must exceed minimum luminance {}",
+                self.max_luminance, self.min_luminance
+            )));
+        }
+        Ok(())
+    }
+
+    // VVenC stores the primaries in SEI order (green, blue, red), followed by the white point and
+    // the maximum and minimum luminance.
+    fn to_ffi(self) -> [u32; 10] {
+        let [red, green, blue] = self.primaries.map(Chromaticity::to_ffi);
+        let [wx, wy] = self.white_point.to_ffi();
+        [
+            green[0],
+            green[1],
+            blue[0],
+            blue[1],
+            red[0],
+            red[1],
+            wx,
+            wy,
+            (self.max_luminance * LUMINANCE_SCALE).round() as u32,
+            (self.min_luminance * LUMINANCE_SCALE).round() as u32,
+        ]
+    }
+
+    fn from_ffi(values: [u32; 10]) -> Option<Self> {
+        if values == [0; 10] {
+            return None;
+        }
+        let [gx, gy, bx, by, rx, ry, wx, wy, max_luminance, min_luminance] = values;
+        Some(Self {
+            primaries: [
+                Chromaticity::from_ffi([rx, ry]),
+                Chromaticity::from_ffi([gx, gy]),
+                Chromaticity::from_ffi([bx, by]),
+            ],
+            white_point: Chromaticity::from_ffi([wx, wy]),
+            max_luminance: max_luminance as f64 / LUMINANCE_SCALE,
+            min_luminance: min_luminance as f64 / LUMINANCE_SCALE,
+        })
+    }
+}
+
+// Parses the x265/ffmpeg syntax `G(x,y)B(x,y)R(x,y)WP(x,y)L(max,min)`, with chromaticities in
+// increments of 0.00002 and luminances in 0.0001 cd/m².
+impl FromStr for MasteringDisplay {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::parameter(format!("invalid mastering display {s:?}"));
+
+        let mut rest = s.trim();
+        let mut pairs = [[0u32; 2]; 5];
+        for (pair, key) in pairs.iter_mut().zip(["G", "B", "R", "WP", "L"]) {
+            let (values, tail) = rest
+                .trim_start()
+                .strip_prefix(key)
+                .and_then(|rest| rest.trim_start().strip_prefix('('))
+                .and_then(|rest| rest.split_once(')'))
+                .ok_or_else(invalid)?;
+            let (a, b) = values.split_once(',').ok_or_else(invalid)?;
+            *pair = [
+                a.trim().parse().map_err(|_| invalid())?,
+                b.trim().parse().map_err(|_| invalid())?,
+            ];
+            rest = tail;
+        }
+        if !rest.trim().is_empty() {
+            return Err(invalid());
+        }
+
+        let [[gx, gy], [bx, by], [rx, ry], [wx, wy], [max, min]] = pairs;
+        let display =
+            Self::from_ffi([gx, gy, bx, by, rx, ry, wx, wy, max, min]).ok_or_else(invalid)?;
+        display.validate()?;
+        Ok(display)
+    }
+}
+
+impl fmt::Display for MasteringDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [gx, gy, bx, by, rx, ry, wx, wy, max, min] = self.to_ffi();
+        write!(
+            f,
+            "G({gx},{gy})B({bx},{by})R({rx},{ry})WP({wx},{wy})L({max},{min})"
+        )
+    }
+}
+
+/// Content light level information (MaxCLL and MaxFALL), in cd/m².
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContentLightLevel {
+    pub max_content_light_level: u16,
+    pub max_frame_average_light_level: u16,
+}
+
+impl ContentLightLevel {
+    fn validate(&self) -> Result<(), Error> {
+        if self.max_frame_average_light_level > self.max_content_light_level {
+            return Err(Error::parameter(format!(
+                "MaxFALL {} exceeds MaxCLL {}",
+                self.max_frame_average_light_level, self.max_content_light_level
+            )));
+        }
+        Ok(())
+    }
+
+    fn to_ffi(self) -> [u32; 2] {
+        [
+            self.max_content_light_level.into(),
+            self.max_frame_average_light_level.into(),
+        ]
+    }
+
+    fn from_ffi([max_cll, max_fall]: [u32; 2]) -> Option<Self> {
+        if max_cll == 0 && max_fall == 0 {
+            return None;
+        }
+        Some(Self {
+            max_content_light_level: max_cll.try_into().unwrap_or(u16::MAX),
+            max_frame_average_light_level: max_fall.try_into().unwrap_or(u16::MAX),
+        })
+    }
+}
+
+// Parses the x265 `max-cll` syntax `MaxCLL,MaxFALL`.
+impl FromStr for ContentLightLevel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::parameter(format!("invalid content light level {s:?}"));
+        let (max_cll, max_fall) = s.split_once(',').ok_or_else(invalid)?;
+        let content_light_level = Self {
+            max_content_light_level: max_cll.trim().parse().map_err(|_| invalid())?,
+            max_frame_average_light_level: max_fall.trim().parse().map_err(|_| invalid())?,
+        };
+        content_light_level.validate()?;
+        Ok(content_light_level)
+    }
+}
+
+impl fmt::Display for ContentLightLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{}",
+            self.max_content_light_level, self.max_frame_average_light_level
+        )
+    }
+}
+
+impl Config {
+    pub fn mastering_display(&self) -> Option<MasteringDisplay> {
+        MasteringDisplay::from_ffi(self.inner.m_masteringDisplay)
+    }
+
+    pub fn set_mastering_display(
+        &mut self,
+        mastering_display: Option<MasteringDisplay>,
+    ) -> Result<&mut Self, Error> {
+        let values = match mastering_display {
+            Some(mastering_display) => {
+                mastering_display.validate()?;
+                mastering_display.to_ffi()
+            }
+            None => [0; 10],
+        };
+        self.inner.m_masteringDisplay = values;
+        Ok(self)
+    }
+
+    pub fn content_light_level(&self) -> Option<ContentLightLevel> {
+        ContentLightLevel::from_ffi(self.inner.m_contentLightLevel)
+    }
+
+    pub fn set_content_light_level(
+        &mut self,
+        content_light_level: Option<ContentLightLevel>,
+    ) -> Result<&mut Self, Error> {
+        let values = match content_light_level {
+            Some(content_light_level) => {
+                content_light_level.validate()?;
+                content_light_level.to_ffi()
+            }
+            None => [0; 2],
+        };
+        self.inner.m_contentLightLevel = values;
+        Ok(self)
+    }
+
+    // Static HDR metadata is only meaningful with a PQ or HLG transfer.
+    pub(crate) fn hdr_metadata_diagnostics(&self) -> Vec<ConfigDiagnostic> {
+        let hdr = matches!(
+            self.hdr_mode(),
+            HdrMode::Pq
+                | HdrMode::PqBt2020
+                | HdrMode::Hlg
+                | HdrMode::HlgBt2020
+                | HdrMode::UserDefined
+        );
+        if hdr {
+            return Vec::new();
+        }
+
+        let mut diagnostics = Vec::new();
+        if self.mastering_display().is_some() {
+            diagnostics.push(ConfigDiagnostic {
+                severity: LogLevel::Warning,
+                message: format!(
+                    "mastering display metadata has no effect with HDR mode {:?}",
+                    self.hdr_mode()
+                ),
+                field: Some("mastering_display"),
+            });
+        }
+        if self.content_light_level().is_some() {
+            diagnostics.push(ConfigDiagnostic {
+                severity: LogLevel::Warning,
+                message: format!(
+                    "content light level metadata has no effect with HDR mode {:?}",
+                    self.hdr_mode()
+                ),
+                field: Some("content_light_level"),
+            });
+        }
+        diagnostics
+    }
+}
@@ -1,25 +1,107 @@
 use std::{
     collections::HashMap,
-    ffi::c_void,
+    ffi::{c_void, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
     path::Path,
     ptr,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, PoisonError},
 };
 
-use vsprintf::vsprintf;
 use vvenc_sys::*;
 
+mod cfg;
+#[cfg(feature = "serde")]
+mod config_serde;
+mod fields;
+mod hdr;
+#[cfg(feature = "log")]
+mod log_crate;
+mod picture_hash;
+mod rate_control;
+mod sei;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "tracing")]
+mod tracing_span;
+mod two_pass;
+mod validation;
+mod version;
+mod vui;
+mod worker;
+pub use fields::{FastMode, ScreenContentMode, ToolMode};
+pub use hdr::{Chromaticity, ContentLightLevel, MasteringDisplay};
+#[cfg(feature = "log")]
+pub use log_crate::LogCrateLogger;
+pub use picture_hash::{PictureHash, PictureHashVerifier};
+pub use rate_control::{Passes, RateControl};
+#[cfg(feature = "async")]
+pub use stream::{encoder_stream, FrameSink, PacketStream};
+#[cfg(feature = "tracing")]
+pub use tracing_span::TracingLogger;
+pub use two_pass::{TwoPassEncoder, TwoPassPackets};
+pub use validation::{ConfigDiagnostic, ValidatedConfig};
+pub use version::{check_version, compile_info, version, Version, LINKED_VERSION};
+pub use vui::{ChromaSampleLocation, ColourPrimaries, MatrixCoefficients, TransferCharacteristics};
+pub use worker::EncoderWorker;
+
 #[derive(Debug)]
 pub struct Encoder<Opaque> {
-    inner: Arc<Mutex<InnerEncoder>>,
+    inner: InnerEncoder,
     _phantom: std::marker::PhantomData<Opaque>,
     // FIXME: this aux map shouldn't be needed when
     // https://github.com/fraunhoferhhi/vvenc/pull/513 gets into a stable VVenC release.
     cts_opaque_map: HashMap<u64, Box<Opaque>>,
+    out_buffer: Vec<u8>,
+    // Payload buffers handed back through `recycle_payload`, reused for the next packets.
+    payload_pool: Vec<Vec<u8>>,
+    // Size `out_buffer` is grown to before encoding, so that no access unit is dropped.
+    max_access_unit_size: usize,
+    finished: bool,
+    // Must be dropped after `inner`, which closes the encoder and stops the callbacks.
+    recon_handler: Option<Box<ReconHandler>>,
+    logger: Option<Arc<LogHandler>>,
+    #[cfg(feature = "tracing")]
+    span: Option<tracing::Span>,
+}
+
+struct ReconHandler {
+    handler: Box<dyn FnMut(&ReconFrame)>,
+    panic: CallbackPanic,
 }
 
-unsafe impl<Opaque> Sync for Encoder<Opaque> {}
-unsafe impl<Opaque> Send for Encoder<Opaque> {}
+impl std::fmt::Debug for ReconHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReconHandler").finish_non_exhaustive()
+    }
+}
+
+// Panics cannot unwind through VVenC, so the callbacks catch them and the encoder reports the
+// first one as `Error::CallbackPanicked` once control is back on the Rust side.
+#[derive(Debug, Default)]
+struct CallbackPanic(Mutex<Option<String>>);
+
+impl CallbackPanic {
+    fn catch(&self, f: impl FnOnce()) {
+        if let Err(payload) = catch_unwind(AssertUnwindSafe(f)) {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "callback panicked".into());
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_insert(message);
+        }
+    }
+
+    fn take(&self) -> Result<(), Error> {
+        match self.0.lock().unwrap_or_else(PoisonError::into_inner).take() {
+            Some(message) => Err(Error::CallbackPanicked { message }),
+            None => Ok(()),
+        }
+    }
+}
 
 #[derive(Debug)]
 struct InnerEncoder {
@@ -38,36 +120,70 @@ pub type EncodeDone = bool;
 
 impl<Opaque: Sized + Sync + Send> Encoder<Opaque> {
     pub fn with_config(mut config: Config) -> Result<Self, Error> {
+        check_version()?;
         let Some(encoder) = ptr::NonNull::new(unsafe { vvenc_encoder_create() }) else {
-            return Err(Error::Initialize);
+            return Err(Error::Initialize {
+                message: "failed to create encoder".into(),
+            });
         };
+        let inner = InnerEncoder { encoder };
         let ret = unsafe { vvenc_encoder_open(encoder.as_ptr(), &mut config.inner) };
         #[allow(non_upper_case_globals)]
         match ret {
-            ErrorCodes_VVENC_OK => Ok(Self {
-                inner: Arc::new(Mutex::new(InnerEncoder { encoder })),
-                _phantom: std::marker::PhantomData::default(),
-                cts_opaque_map: HashMap::new(),
-            }),
-            _ => Err(Error::new(ret)),
+            ErrorCodes_VVENC_OK => {
+                let mut encoder = Self {
+                    inner,
+                    _phantom: std::marker::PhantomData::default(),
+                    cts_opaque_map: HashMap::new(),
+                    out_buffer: Vec::new(),
+                    payload_pool: Vec::new(),
+                    max_access_unit_size: 0,
+                    finished: false,
+                    recon_handler: None,
+                    logger: config.logger.clone(),
+                    #[cfg(feature = "tracing")]
+                    span: config.span.clone(),
+                };
+                encoder.take_callback_panic()?;
+                // Sized from the opened configuration, where automatic values are resolved.
+                encoder.max_access_unit_size = encoder.config().max_access_unit_size();
+                encoder.out_buffer = vec![0; encoder.max_access_unit_size];
+                Ok(encoder)
+            }
+            _ => {
+                // Not left behind in the handler shared by clones of the config.
+                if let Some(logger) = &config.logger {
+                    logger.panic.take()?;
+                }
+                Err(Error::from_open(ret, &inner, &config))
+            }
         }
     }
 
+    // Reports a panic caught in one of the callbacks since the last call. The logger may be shared
+    // with other encoders, so its panics can surface in any of them.
+    fn take_callback_panic(&self) -> Result<(), Error> {
+        if let Some(logger) = &self.logger {
+            logger.panic.take()?;
+        }
+        if let Some(recon_handler) = &self.recon_handler {
+            recon_handler.panic.take()?;
+        }
+        Ok(())
+    }
+
     pub fn init_pass(&mut self, pass: i32, stats_file: &Path) -> Result<(), Error> {
-        let ret = unsafe {
-            vvenc_init_pass(
-                self.inner.lock().unwrap().encoder.as_ptr(),
-                pass,
-                stats_file
-                    .as_os_str()
-                    .to_str()
-                    .ok_or(Error::Parameter)?
-                    .as_ptr() as *const i8,
-            )
-        };
+        let stats_file = stats_file
+            .to_str()
+            .and_then(|stats_file| CString::new(stats_file).ok())
+            .ok_or_else(|| Error::parameter("stats file path must be valid UTF-8 without NUL"))?;
+        let ret =
+            unsafe { vvenc_init_pass(self.inner.encoder.as_ptr(), pass, stats_file.as_ptr()) };
+        self.take_callback_panic()?;
         if ret != ErrorCodes_VVENC_OK {
-            return Err(Error::new(ret));
+            return Err(Error::from_encoder(ret, &self.inner));
         }
+        self.finished = false;
         Ok(())
     }
 
@@ -76,98 +192,327 @@ impl<Opaque: Sized + Sync + Send> Encoder<Opaque> {
         yuv_buffer: &mut YUVBuffer<Opaque>,
         out_data: &'b mut [u8],
     ) -> Result<Option<AccessUnit<'b, Opaque>>, Error> {
-        if let Some(cts) = yuv_buffer.cts() {
-            if let Some(opaque) = yuv_buffer.opaque.take() {
-                self.cts_opaque_map.insert(cts, opaque);
-            }
-        }
+        let (au, _) = self.encode_raw(Some(yuv_buffer), out_data)?;
+        Ok(au)
+    }
 
-        let mut au = AccessUnit::new(out_data);
-        let mut encode_done = false;
-        let ret = unsafe {
-            vvenc_encode(
-                self.inner.lock().unwrap().encoder.as_ptr(),
-                &mut yuv_buffer.inner,
-                &mut au.inner,
-                &mut encode_done,
-            )
-        };
+    pub fn flush<'b>(
+        &mut self,
+        out_data: &'b mut [u8],
+    ) -> Result<Option<(AccessUnit<'b, Opaque>, EncodeDone)>, Error> {
+        let (au, encode_done) = self.encode_raw(None, out_data)?;
+        Ok(au.map(|au| (au, encode_done)))
+    }
 
-        if ret != ErrorCodes_VVENC_OK {
-            return Err(Error::new(ret));
+    pub fn drain(&mut self) -> Drain<'_, Opaque> {
+        Drain {
+            encoder: self,
+            failed: false,
         }
+    }
 
-        Ok((!au.payload().is_empty()).then(|| {
-            if let Some(cts) = au.cts() {
-                if let Some(opaque) = self.cts_opaque_map.remove(&cts) {
-                    au.set_opaque(opaque);
-                }
-            }
-            au
-        }))
+    pub fn is_finished(&self) -> bool {
+        self.finished
     }
 
-    pub fn flush<'b>(
+    pub fn encode_packet(
+        &mut self,
+        yuv_buffer: &mut YUVBuffer<Opaque>,
+    ) -> Result<Option<Packet<Opaque>>, Error> {
+        let (packet, _) = self.encode_raw_packet(Some(yuv_buffer))?;
+        Ok(packet)
+    }
+
+    pub fn flush_packet(&mut self) -> Result<Option<(Packet<Opaque>, EncodeDone)>, Error> {
+        let (packet, encode_done) = self.encode_raw_packet(None)?;
+        Ok(packet.map(|packet| (packet, encode_done)))
+    }
+
+    /// Hands a buffer to the encoder for the packet methods to encode into, e.g. one kept from a
+    /// previous encoder. It is grown to the worst-case access unit size before use.
+    pub fn set_packet_buffer(&mut self, buffer: Vec<u8>) {
+        self.out_buffer = buffer;
+    }
+
+    /// Hands the payload of a consumed [`Packet`] back to the encoder, which fills it with the
+    /// payload of a later packet instead of allocating a new one.
+    pub fn recycle_payload(&mut self, payload: Vec<u8>) {
+        self.payload_pool.push(payload);
+    }
+
+    // VVenC drops the access unit when it does not fit in the output buffer, so the pooled buffer
+    // is grown to the worst-case size beforehand rather than after a failure.
+    pub(crate) fn encode_raw_packet(
+        &mut self,
+        yuv_buffer: Option<&mut YUVBuffer<Opaque>>,
+    ) -> Result<(Option<Packet<Opaque>>, EncodeDone), Error> {
+        let mut out_buffer = std::mem::take(&mut self.out_buffer);
+        if out_buffer.len() < self.max_access_unit_size {
+            out_buffer.resize(self.max_access_unit_size, 0);
+        }
+        let ret = self
+            .encode_raw(yuv_buffer, &mut out_buffer)
+            .map(|(au, encode_done)| {
+                let packet = au.map(|au| {
+                    Packet::with_payload(au, self.payload_pool.pop().unwrap_or_default())
+                });
+                (packet, encode_done)
+            });
+        self.out_buffer = out_buffer;
+        ret
+    }
+
+    pub(crate) fn encode_raw<'b>(
         &mut self,
+        yuv_buffer: Option<&mut YUVBuffer<Opaque>>,
         out_data: &'b mut [u8],
-    ) -> Result<Option<(AccessUnit<'b, Opaque>, EncodeDone)>, Error> {
+    ) -> Result<(Option<AccessUnit<'b, Opaque>>, EncodeDone), Error> {
+        if self.finished {
+            return Err(Error::Finished);
+        }
+
+        let yuv_buffer = match yuv_buffer {
+            Some(yuv_buffer) => {
+                if let Some(cts) = yuv_buffer.cts() {
+                    if let Some(opaque) = yuv_buffer.opaque.take() {
+                        self.cts_opaque_map.insert(cts, opaque);
+                    }
+                }
+                &mut yuv_buffer.inner as *mut vvencYUVBuffer
+            }
+            None => ptr::null_mut(),
+        };
+
         let mut au = AccessUnit::new(out_data);
         let mut encode_done = false;
+        #[cfg(feature = "tracing")]
+        let started = std::time::Instant::now();
         let ret = unsafe {
             vvenc_encode(
-                self.inner.lock().unwrap().encoder.as_ptr(),
-                std::ptr::null_mut(),
+                self.inner.encoder.as_ptr(),
+                yuv_buffer,
                 &mut au.inner,
                 &mut encode_done,
             )
         };
+        #[cfg(feature = "tracing")]
+        if let Some(span) = &self.span {
+            tracing_span::encode_event(
+                span,
+                yuv_buffer.is_null(),
+                started.elapsed(),
+                au.payload().len(),
+            );
+        }
 
+        self.take_callback_panic()?;
         if ret != ErrorCodes_VVENC_OK {
-            return Err(Error::new(ret));
+            return Err(Error::from_encoder(ret, &self.inner));
         }
+        self.finished = encode_done;
 
-        Ok((!au.payload().is_empty()).then(|| {
+        let au = (!au.payload().is_empty()).then(|| {
             if let Some(cts) = au.cts() {
                 if let Some(opaque) = self.cts_opaque_map.remove(&cts) {
                     au.set_opaque(opaque);
                 }
             }
-            (au, encode_done)
-        }))
+            au
+        });
+        Ok((au, encode_done))
     }
 
     pub fn config(&self) -> Config {
         let mut config = Config::default();
+        unsafe { vvenc_get_config(self.inner.encoder.as_ptr(), &mut config.inner) };
+        config.logger = self.logger.clone();
+        #[cfg(feature = "tracing")]
+        {
+            config.span = self.span.clone();
+        }
+        config
+    }
+
+    pub fn headers(&mut self) -> Result<Vec<u8>, Error> {
+        if self.out_buffer.len() < self.max_access_unit_size {
+            self.out_buffer.resize(self.max_access_unit_size, 0);
+        }
+        let mut au = AccessUnit::<Opaque>::new(&mut self.out_buffer);
+        let ret = unsafe { vvenc_get_headers(self.inner.encoder.as_ptr(), &mut au.inner) };
+        let headers = au.payload().to_vec();
+        self.take_callback_panic()?;
+        if ret != ErrorCodes_VVENC_OK {
+            return Err(Error::from_encoder(ret, &self.inner));
+        }
+        Ok(headers)
+    }
+
+    /// Parameter set NAL units without their Annex B start codes, as expected by e.g. MP4 sample
+    /// descriptions.
+    pub fn header_nal_units(&mut self) -> Result<Vec<Vec<u8>>, Error> {
+        let headers = self.headers()?;
+        Ok(nal_units(&headers).map(<[u8]>::to_vec).collect())
+    }
+
+    pub fn set_recon_handler(&mut self, handler: impl FnMut(&ReconFrame) + 'static) {
+        let mut handler = Box::new(ReconHandler {
+            handler: Box::new(handler),
+            panic: CallbackPanic::default(),
+        });
         unsafe {
-            vvenc_get_config(
-                self.inner.lock().unwrap().encoder.as_ptr(),
-                &mut config.inner,
+            vvenc_encoder_set_RecYUVBufferCallback(
+                self.inner.encoder.as_ptr(),
+                handler.as_mut() as *mut ReconHandler as *mut c_void,
+                Some(recon_callback),
             )
         };
-        config
+        self.recon_handler = Some(handler);
+    }
+
+    pub fn reconfigure(&mut self, f: impl FnOnce(&mut Config)) -> Result<(), Error> {
+        let current = self.config();
+        let mut config = current.clone();
+        f(&mut config);
+
+        let rejected = config.static_changes(&current);
+        if !rejected.is_empty() {
+            return Err(Error::Reconfigure(rejected));
+        }
+
+        let ret = unsafe { vvenc_reconfig(self.inner.encoder.as_ptr(), &config.inner) };
+        self.take_callback_panic()?;
+        if ret != ErrorCodes_VVENC_OK {
+            return Err(Error::from_encoder(ret, &self.inner));
+        }
+        self.max_access_unit_size = self.config().max_access_unit_size();
+        Ok(())
+    }
+}
+
+fn nal_units(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i..i + 3] == [0, 0, 1] {
+            starts.push(i + 3);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+
+    (0..starts.len()).map(move |n| {
+        let end = starts.get(n + 1).map_or(data.len(), |next| next - 3);
+        let mut nal = &data[starts[n]..end];
+        // NAL units never end with a zero byte, those belong to the next (4-byte) start code.
+        while let [rest @ .., 0] = nal {
+            nal = rest;
+        }
+        nal
+    })
+}
+
+unsafe extern "C" fn recon_callback(ctx: *mut c_void, yuv_buffer: *mut vvencYUVBuffer) {
+    let handler = &mut *(ctx as *mut ReconHandler);
+    if let Some(inner) = yuv_buffer.as_ref() {
+        let callback = &mut handler.handler;
+        handler.panic.catch(|| callback(&ReconFrame { inner }));
+    }
+}
+
+#[derive(Debug)]
+pub struct ReconFrame<'a> {
+    inner: &'a vvencYUVBuffer,
+}
+
+impl ReconFrame<'_> {
+    pub fn plane(&self, component: YUVComponent) -> Plane<'_> {
+        Plane {
+            inner: self.inner.planes[component as usize],
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// VVenC reports the picture order count of reconstructed pictures as their sequence number.
+    pub fn poc(&self) -> u64 {
+        self.inner.sequenceNumber
+    }
+
+    pub fn cts(&self) -> Option<u64> {
+        self.inner.ctsValid.then_some(self.inner.cts)
     }
 }
 
 #[derive(Debug)]
+pub struct Drain<'a, Opaque> {
+    encoder: &'a mut Encoder<Opaque>,
+    failed: bool,
+}
+
+impl<Opaque: Sized + Sync + Send> Iterator for Drain<'_, Opaque> {
+    type Item = Result<Packet<Opaque>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed && !self.encoder.finished {
+            match self.encoder.encode_raw_packet(None) {
+                Ok((Some(packet), _)) => return Some(Ok(packet)),
+                Ok((None, _)) => continue,
+                Err(error) => {
+                    self.failed = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<Opaque: Sized + Sync + Send> std::iter::FusedIterator for Drain<'_, Opaque> {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rational {
     pub num: i32,
     pub den: i32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u8", into = "u8")
+)]
 pub struct Qp(pub u8);
 
+impl TryFrom<u8> for Qp {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Qp> for u8 {
+    fn from(qp: Qp) -> Self {
+        qp.0
+    }
+}
+
 impl Qp {
     pub fn new(value: u8) -> Result<Self, Error> {
         if value > 63 {
-            return Err(Error::Parameter);
+            return Err(Error::parameter(format!(
+                "QP {value} is out of range [0, 63]"
+            )));
         }
         Ok(Self(value))
     }
 
     fn from_ffi(value: i32) -> Result<Self, Error> {
-        if value > 63 {
-            return Err(Error::Parameter);
+        if !(0..=63).contains(&value) {
+            return Err(Error::parameter(format!(
+                "QP {value} is out of range [0, 63]"
+            )));
         }
         Ok(Self(value as u8))
     }
@@ -177,7 +522,9 @@ impl Qp {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum LogLevel {
     Silent = 0,
     Error = 1,
@@ -189,17 +536,17 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
-    fn from_ffi(value: u32) -> Self {
+    fn try_from_ffi(value: u32) -> Option<Self> {
         #[allow(non_upper_case_globals)]
         match value {
-            vvencMsgLevel_VVENC_SILENT => Self::Silent,
-            vvencMsgLevel_VVENC_ERROR => Self::Error,
-            vvencMsgLevel_VVENC_WARNING => Self::Warning,
-            vvencMsgLevel_VVENC_INFO => Self::Info,
-            vvencMsgLevel_VVENC_NOTICE => Self::Notice,
-            vvencMsgLevel_VVENC_VERBOSE => Self::Verbose,
-            vvencMsgLevel_VVENC_DETAILS => Self::Details,
-            _ => unreachable!(),
+            vvencMsgLevel_VVENC_SILENT => Some(Self::Silent),
+            vvencMsgLevel_VVENC_ERROR => Some(Self::Error),
+            vvencMsgLevel_VVENC_WARNING => Some(Self::Warning),
+            vvencMsgLevel_VVENC_INFO => Some(Self::Info),
+            vvencMsgLevel_VVENC_NOTICE => Some(Self::Notice),
+            vvencMsgLevel_VVENC_VERBOSE => Some(Self::Verbose),
+            vvencMsgLevel_VVENC_DETAILS => Some(Self::Details),
+            _ => None,
         }
     }
 
@@ -220,7 +567,32 @@ pub trait Logger {
     fn log(&self, level: LogLevel, message: &str);
 }
 
+impl<F: Fn(LogLevel, &str)> Logger for F {
+    fn log(&self, level: LogLevel, message: &str) {
+        self(level, message)
+    }
+}
+
+// Target of the `msgCtx` pointer handed to VVenC. It is shared by every Config clone and Encoder
+// that may still call it, and freed with the last of them.
+//
+// `context` must stay the first field: the shim passes a pointer to it back to `log_message`.
+#[repr(C)]
+struct LogHandler {
+    context: vvencRsLogContext,
+    logger: Box<dyn Logger + Send + Sync>,
+    panic: CallbackPanic,
+}
+
+impl std::fmt::Debug for LogHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogHandler").finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Profile {
     Auto,
     Main10,
@@ -253,29 +625,30 @@ impl Profile {
         }
     }
 
-    #[inline]
-    fn from_ffi(value: vvencProfile) -> Self {
+    fn try_from_ffi(value: vvencProfile) -> Option<Self> {
         #[allow(non_upper_case_globals)]
         match value {
-            vvencProfile_VVENC_PROFILE_AUTO => Self::Auto,
-            vvencProfile_VVENC_MAIN_10 => Self::Main10,
-            vvencProfile_VVENC_MAIN_10_STILL_PICTURE => Self::Main10StillPicture,
-            vvencProfile_VVENC_MAIN_10_444 => Self::Main10444,
-            vvencProfile_VVENC_MAIN_10_444_STILL_PICTURE => Self::Main10444StillPicture,
-            vvencProfile_VVENC_MULTILAYER_MAIN_10 => Self::MultilayerMain10,
+            vvencProfile_VVENC_PROFILE_AUTO => Some(Self::Auto),
+            vvencProfile_VVENC_MAIN_10 => Some(Self::Main10),
+            vvencProfile_VVENC_MAIN_10_STILL_PICTURE => Some(Self::Main10StillPicture),
+            vvencProfile_VVENC_MAIN_10_444 => Some(Self::Main10444),
+            vvencProfile_VVENC_MAIN_10_444_STILL_PICTURE => Some(Self::Main10444StillPicture),
+            vvencProfile_VVENC_MULTILAYER_MAIN_10 => Some(Self::MultilayerMain10),
             vvencProfile_VVENC_MULTILAYER_MAIN_10_STILL_PICTURE => {
-                Self::MultilayerMain10StillPicture
+                Some(Self::MultilayerMain10StillPicture)
             }
-            vvencProfile_VVENC_MULTILAYER_MAIN_10_444 => Self::MultilayerMain10444,
+            vvencProfile_VVENC_MULTILAYER_MAIN_10_444 => Some(Self::MultilayerMain10444),
             vvencProfile_VVENC_MULTILAYER_MAIN_10_444_STILL_PICTURE => {
-                Self::MultilayerMain10444StillPicture
+                Some(Self::MultilayerMain10444StillPicture)
             }
-            _ => unreachable!(),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Tier {
     Main,
     High,
@@ -290,18 +663,19 @@ impl Tier {
         }
     }
 
-    #[inline]
-    fn from_ffi(value: vvencTier) -> Self {
+    fn try_from_ffi(value: vvencTier) -> Option<Self> {
         #[allow(non_upper_case_globals)]
         match value {
-            vvencTier_VVENC_TIER_MAIN => Self::Main,
-            vvencTier_VVENC_TIER_HIGH => Self::High,
-            _ => unreachable!(),
+            vvencTier_VVENC_TIER_MAIN => Some(Self::Main),
+            vvencTier_VVENC_TIER_HIGH => Some(Self::High),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Level {
     Auto,
     Level1,
@@ -344,32 +718,33 @@ impl Level {
         }
     }
 
-    #[inline]
-    fn from_ffi(value: vvencLevel) -> Self {
+    fn try_from_ffi(value: vvencLevel) -> Option<Self> {
         #[allow(non_upper_case_globals)]
         match value {
-            vvencLevel_VVENC_LEVEL_AUTO => Self::Auto,
-            vvencLevel_VVENC_LEVEL1 => Self::Level1,
-            vvencLevel_VVENC_LEVEL2 => Self::Level2,
-            vvencLevel_VVENC_LEVEL2_1 => Self::Level2_1,
-            vvencLevel_VVENC_LEVEL3 => Self::Level3,
-            vvencLevel_VVENC_LEVEL3_1 => Self::Level3_1,
-            vvencLevel_VVENC_LEVEL4 => Self::Level4,
-            vvencLevel_VVENC_LEVEL4_1 => Self::Level4_1,
-            vvencLevel_VVENC_LEVEL5 => Self::Level5,
-            vvencLevel_VVENC_LEVEL5_1 => Self::Level5_1,
-            vvencLevel_VVENC_LEVEL5_2 => Self::Level5_2,
-            vvencLevel_VVENC_LEVEL6 => Self::Level6,
-            vvencLevel_VVENC_LEVEL6_1 => Self::Level6_1,
-            vvencLevel_VVENC_LEVEL6_2 => Self::Level6_2,
-            vvencLevel_VVENC_LEVEL6_3 => Self::Level6_3,
-            vvencLevel_VVENC_LEVEL15_5 => Self::Level15_5,
-            _ => unreachable!(),
+            vvencLevel_VVENC_LEVEL_AUTO => Some(Self::Auto),
+            vvencLevel_VVENC_LEVEL1 => Some(Self::Level1),
+            vvencLevel_VVENC_LEVEL2 => Some(Self::Level2),
+            vvencLevel_VVENC_LEVEL2_1 => Some(Self::Level2_1),
+            vvencLevel_VVENC_LEVEL3 => Some(Self::Level3),
+            vvencLevel_VVENC_LEVEL3_1 => Some(Self::Level3_1),
+            vvencLevel_VVENC_LEVEL4 => Some(Self::Level4),
+            vvencLevel_VVENC_LEVEL4_1 => Some(Self::Level4_1),
+            vvencLevel_VVENC_LEVEL5 => Some(Self::Level5),
+            vvencLevel_VVENC_LEVEL5_1 => Some(Self::Level5_1),
+            vvencLevel_VVENC_LEVEL5_2 => Some(Self::Level5_2),
+            vvencLevel_VVENC_LEVEL6 => Some(Self::Level6),
+            vvencLevel_VVENC_LEVEL6_1 => Some(Self::Level6_1),
+            vvencLevel_VVENC_LEVEL6_2 => Some(Self::Level6_2),
+            vvencLevel_VVENC_LEVEL6_3 => Some(Self::Level6_3),
+            vvencLevel_VVENC_LEVEL15_5 => Some(Self::Level15_5),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum DecodingRefreshType {
     None,
     Cra,
@@ -394,23 +769,24 @@ impl DecodingRefreshType {
         }
     }
 
-    #[inline]
-    fn from_ffi(value: vvencDecodingRefreshType) -> Self {
+    fn try_from_ffi(value: vvencDecodingRefreshType) -> Option<Self> {
         #[allow(non_upper_case_globals)]
         match value {
-            vvencDecodingRefreshType_VVENC_DRT_NONE => Self::None,
-            vvencDecodingRefreshType_VVENC_DRT_CRA => Self::Cra,
-            vvencDecodingRefreshType_VVENC_DRT_IDR => Self::Idr,
-            vvencDecodingRefreshType_VVENC_DRT_RECOVERY_POINT_SEI => Self::RecoveryPointSei,
-            vvencDecodingRefreshType_VVENC_DRT_IDR2 => Self::Idr2,
-            vvencDecodingRefreshType_VVENC_DRT_CRA_CRE => Self::CraCre,
-            vvencDecodingRefreshType_VVENC_DRT_IDR_NO_RADL => Self::IdrNoRadl,
-            _ => unreachable!(),
+            vvencDecodingRefreshType_VVENC_DRT_NONE => Some(Self::None),
+            vvencDecodingRefreshType_VVENC_DRT_CRA => Some(Self::Cra),
+            vvencDecodingRefreshType_VVENC_DRT_IDR => Some(Self::Idr),
+            vvencDecodingRefreshType_VVENC_DRT_RECOVERY_POINT_SEI => Some(Self::RecoveryPointSei),
+            vvencDecodingRefreshType_VVENC_DRT_IDR2 => Some(Self::Idr2),
+            vvencDecodingRefreshType_VVENC_DRT_CRA_CRE => Some(Self::CraCre),
+            vvencDecodingRefreshType_VVENC_DRT_IDR_NO_RADL => Some(Self::IdrNoRadl),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SegmentMode {
     Off,
     First,
@@ -429,20 +805,21 @@ impl SegmentMode {
         }
     }
 
-    #[inline]
-    fn from_ffi(value: vvencSegmentMode) -> Self {
+    fn try_from_ffi(value: vvencSegmentMode) -> Option<Self> {
         #[allow(non_upper_case_globals)]
         match value {
-            vvencSegmentMode_VVENC_SEG_OFF => Self::Off,
-            vvencSegmentMode_VVENC_SEG_FIRST => Self::First,
-            vvencSegmentMode_VVENC_SEG_MID => Self::Mid,
-            vvencSegmentMode_VVENC_SEG_LAST => Self::Last,
-            _ => unreachable!(),
+            vvencSegmentMode_VVENC_SEG_OFF => Some(Self::Off),
+            vvencSegmentMode_VVENC_SEG_FIRST => Some(Self::First),
+            vvencSegmentMode_VVENC_SEG_MID => Some(Self::Mid),
+            vvencSegmentMode_VVENC_SEG_LAST => Some(Self::Last),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum HdrMode {
     Off,
     Pq,
@@ -471,20 +848,19 @@ impl HdrMode {
         }
     }
 
-    #[inline]
-    fn from_ffi(value: vvencHDRMode) -> Self {
+    fn try_from_ffi(value: vvencHDRMode) -> Option<Self> {
         #[allow(non_upper_case_globals)]
         match value {
-            vvencHDRMode_VVENC_HDR_OFF => Self::Off,
-            vvencHDRMode_VVENC_HDR_PQ => Self::Pq,
-            vvencHDRMode_VVENC_HDR_HLG => Self::Hlg,
-            vvencHDRMode_VVENC_HDR_PQ_BT2020 => Self::PqBt2020,
-            vvencHDRMode_VVENC_HDR_HLG_BT2020 => Self::HlgBt2020,
-            vvencHDRMode_VVENC_HDR_USER_DEFINED => Self::UserDefined,
-            vvencHDRMode_VVENC_SDR_BT709 => Self::SdrBt709,
-            vvencHDRMode_VVENC_SDR_BT2020 => Self::SdrBt2020,
-            vvencHDRMode_VVENC_SDR_BT470BG => Self::SdrBt470bg,
-            _ => unreachable!(),
+            vvencHDRMode_VVENC_HDR_OFF => Some(Self::Off),
+            vvencHDRMode_VVENC_HDR_PQ => Some(Self::Pq),
+            vvencHDRMode_VVENC_HDR_HLG => Some(Self::Hlg),
+            vvencHDRMode_VVENC_HDR_PQ_BT2020 => Some(Self::PqBt2020),
+            vvencHDRMode_VVENC_HDR_HLG_BT2020 => Some(Self::HlgBt2020),
+            vvencHDRMode_VVENC_HDR_USER_DEFINED => Some(Self::UserDefined),
+            vvencHDRMode_VVENC_SDR_BT709 => Some(Self::SdrBt709),
+            vvencHDRMode_VVENC_SDR_BT2020 => Some(Self::SdrBt2020),
+            vvencHDRMode_VVENC_SDR_BT470BG => Some(Self::SdrBt470bg),
+            _ => None,
         }
     }
 }
@@ -492,6 +868,9 @@ impl HdrMode {
 #[derive(Debug, Clone)]
 pub struct Config {
     inner: vvenc_config,
+    logger: Option<Arc<LogHandler>>,
+    #[cfg(feature = "tracing")]
+    span: Option<tracing::Span>,
 }
 
 unsafe impl Send for Config {}
@@ -503,7 +882,12 @@ impl Default for Config {
         unsafe {
             vvenc_config_default(&mut inner);
         }
-        Self { inner }
+        Self {
+            inner,
+            logger: None,
+            #[cfg(feature = "tracing")]
+            span: None,
+        }
     }
 }
 
@@ -551,6 +935,71 @@ impl Config {
         Ok(self)
     }
 
+    /// Accepts the option names and values understood by vvencapp, e.g. `("preset", "fast")`.
+    pub fn set_param(&mut self, name: &str, value: &str) -> Result<&mut Self, Error> {
+        let invalid_value = || Error::InvalidParameterValue {
+            name: name.to_owned(),
+            value: value.to_owned(),
+        };
+        let c_name = CString::new(name).map_err(|_| Error::UnknownParameter(name.to_owned()))?;
+        let c_value = CString::new(value).map_err(|_| invalid_value())?;
+        let previous = self.inner;
+        let ret = unsafe { vvenc_set_param(&mut self.inner, c_name.as_ptr(), c_value.as_ptr()) };
+        let error = match ret {
+            0 => {
+                return self
+                    .check_typed_members(previous, name, value)
+                    .map(|_| self)
+            }
+            VVENC_PARAM_BAD_NAME => Error::UnknownParameter(name.to_owned()),
+            VVENC_PARAM_BAD_VALUE => invalid_value(),
+            ret => Error::new(ret),
+        };
+        self.inner = previous;
+        Err(error)
+    }
+
+    /// Applies a `key=value:key2=value2` list, leaving the config untouched if any parameter is
+    /// rejected. A key without a value is treated as a flag set to 1, and a backslash escapes the
+    /// next character, e.g. a `:` inside a value.
+    pub fn set_params(&mut self, params: &str) -> Result<&mut Self, Error> {
+        let previous = self.inner;
+        for (name, value) in split_params(params) {
+            if let Err(error) = self.set_param(&name, &value) {
+                self.inner = previous;
+                return Err(error);
+            }
+        }
+        Ok(self)
+    }
+
+    // VVenC stores any integer it parses, while the typed getters only understand the values VVenC
+    // defines, so a change leaving one of them unreadable is rolled back.
+    pub(crate) fn check_typed_members(
+        &mut self,
+        previous: vvenc_config,
+        name: &str,
+        value: &str,
+    ) -> Result<(), Error> {
+        let inner = &self.inner;
+        let valid = Qp::from_ffi(inner.m_QP).is_ok()
+            && Profile::try_from_ffi(inner.m_profile).is_some()
+            && Tier::try_from_ffi(inner.m_levelTier).is_some()
+            && Level::try_from_ffi(inner.m_level).is_some()
+            && DecodingRefreshType::try_from_ffi(inner.m_DecodingRefreshType).is_some()
+            && HdrMode::try_from_ffi(inner.m_HdrMode).is_some()
+            && SegmentMode::try_from_ffi(inner.m_SegmentMode).is_some()
+            && LogLevel::try_from_ffi(inner.m_verbosity).is_some();
+        if !valid {
+            self.inner = previous;
+            return Err(Error::InvalidParameterValue {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            });
+        }
+        Ok(())
+    }
+
     pub fn ticks_per_second(&self) -> i32 {
         self.inner.m_TicksPerSecond
     }
@@ -615,7 +1064,7 @@ impl Config {
     }
 
     pub fn profile(&self) -> Profile {
-        Profile::from_ffi(self.inner.m_profile)
+        from_ffi_checked(Profile::try_from_ffi(self.inner.m_profile))
     }
 
     pub fn set_profile(&mut self, profile: Profile) -> &mut Self {
@@ -624,7 +1073,7 @@ impl Config {
     }
 
     pub fn tier(&self) -> Tier {
-        Tier::from_ffi(self.inner.m_levelTier)
+        from_ffi_checked(Tier::try_from_ffi(self.inner.m_levelTier))
     }
 
     pub fn set_tier(&mut self, tier: Tier) -> &mut Self {
@@ -633,7 +1082,7 @@ impl Config {
     }
 
     pub fn level(&self) -> Level {
-        Level::from_ffi(self.inner.m_level)
+        from_ffi_checked(Level::try_from_ffi(self.inner.m_level))
     }
 
     pub fn set_level(&mut self, level: Level) -> &mut Self {
@@ -660,7 +1109,9 @@ impl Config {
     }
 
     pub fn decoding_refresh_type(&self) -> DecodingRefreshType {
-        DecodingRefreshType::from_ffi(self.inner.m_DecodingRefreshType)
+        from_ffi_checked(DecodingRefreshType::try_from_ffi(
+            self.inner.m_DecodingRefreshType,
+        ))
     }
 
     pub fn set_decoding_refresh_type(
@@ -708,7 +1159,7 @@ impl Config {
     }
 
     pub fn hdr_mode(&self) -> HdrMode {
-        HdrMode::from_ffi(self.inner.m_HdrMode)
+        from_ffi_checked(HdrMode::try_from_ffi(self.inner.m_HdrMode))
     }
 
     pub fn set_hdr_mode(&mut self, hdr_mode: HdrMode) -> &mut Self {
@@ -717,7 +1168,7 @@ impl Config {
     }
 
     pub fn segment_mode(&self) -> SegmentMode {
-        SegmentMode::from_ffi(self.inner.m_SegmentMode)
+        from_ffi_checked(SegmentMode::try_from_ffi(self.inner.m_SegmentMode))
     }
 
     pub fn set_segment_mode(&mut self, segment_mode: SegmentMode) -> &mut Self {
@@ -765,7 +1216,7 @@ impl Config {
     }
 
     pub fn log_level(&self) -> LogLevel {
-        LogLevel::from_ffi(self.inner.m_verbosity)
+        from_ffi_checked(LogLevel::try_from_ffi(self.inner.m_verbosity))
     }
 
     pub fn set_log_level(&mut self, log_level: LogLevel) -> &mut Self {
@@ -773,66 +1224,238 @@ impl Config {
         self
     }
 
-    pub fn set_logger(&mut self, logger: Box<dyn Logger>) -> &mut Self {
-        let logger = Box::new(logger);
+    // Upper bound of an access unit, which carries a single picture: twice the raw picture at the
+    // internal bit depth, which even lossless coding stays under, plus room for parameter sets and
+    // SEI messages. vvencapp's heuristic of 2-3 bytes per luma sample falls short at high bit depths.
+    fn max_access_unit_size(&self) -> usize {
+        let luma_samples = self.width().max(0) as usize * self.height().max(0) as usize;
+        let samples = match self.internal_chroma_format() {
+            ChromaFormat::Chroma400 => luma_samples,
+            ChromaFormat::Chroma420 => luma_samples * 3 / 2,
+            ChromaFormat::Chroma422 => luma_samples * 2,
+            _ => luma_samples * 3,
+        };
+        let bit_depth = self.internal_bit_depth()[0].clamp(8, 16) as usize;
+        2 * samples * bit_depth / 8 + 64 * 1024
+    }
+
+    pub fn set_logger(&mut self, logger: impl Logger + Send + Sync + 'static) -> &mut Self {
+        let logger = Arc::new(LogHandler {
+            context: vvencRsLogContext {
+                callback: Some(log_message),
+            },
+            logger: Box::new(logger),
+            panic: CallbackPanic::default(),
+        });
+        // Messages are formatted on the C side, as `va_list` has a different shape on each target.
         unsafe {
             vvenc_set_msg_callback(
                 &mut self.inner,
-                Box::into_raw(logger) as *mut c_void,
-                Some(log_callback),
+                &logger.context as *const vvencRsLogContext as *mut c_void,
+                Some(vvenc_rs_log_callback),
             )
         };
+        self.logger = Some(logger);
+        // The span belonged to the replaced logger; `with_tracing` sets it again afterwards.
+        #[cfg(feature = "tracing")]
+        {
+            self.span = None;
+        }
         self
     }
 }
 
-unsafe extern "C" fn log_callback(
-    ctx: *mut ::std::os::raw::c_void,
+unsafe extern "C" fn log_message(
+    ctx: *const vvencRsLogContext,
     level: ::std::os::raw::c_int,
-    fmt: *const ::std::os::raw::c_char,
-    args: *mut __va_list_tag,
+    message: *const ::std::os::raw::c_char,
 ) {
-    let logger = &*(ctx as *mut Box<dyn Logger>);
-    let level = LogLevel::from_ffi(level as u32);
-    let message = vsprintf(fmt, args).unwrap();
-    logger.log(level, &message);
+    let handler = &*(ctx as *const LogHandler);
+    // Everything that may panic, conversions included, runs inside `catch`.
+    handler.panic.catch(|| {
+        // Levels VVenC adds later are passed on rather than treated as a failure of the logger.
+        let level = LogLevel::try_from_ffi(level as u32).unwrap_or(LogLevel::Details);
+        let message = if message.is_null() {
+            "<unformatted VVenC message>".into()
+        } else {
+            CStr::from_ptr(message).to_string_lossy()
+        };
+        handler.logger.log(level, &message)
+    });
 }
 
 #[derive(Debug, PartialEq, thiserror::Error)]
 pub enum Error {
-    #[error("unspecified error")]
-    Unspecified,
-    #[error("failed to initialize encoder")]
-    Initialize,
-    #[error("failed to allocate resources")]
-    Allocate,
-    #[error("not enough memory")]
-    NotEnoughMemory,
-    #[error("invalid parameter")]
-    Parameter,
-    #[error("operation not supported")]
-    NotSupported,
-    #[error("restart required")]
-    RestartRequired,
-    #[error("CPU error")]
-    Cpu,
-    #[error("unknown error with code {0}")]
-    Unknown(i32),
+    #[error("{message}")]
+    Unspecified { message: String },
+    #[error("{message}")]
+    Initialize { message: String },
+    #[error("{message}")]
+    Allocate { message: String },
+    #[error("{message}")]
+    NotEnoughMemory { message: String },
+    #[error("{message}")]
+    Parameter { message: String },
+    #[error("{message}")]
+    NotSupported { message: String },
+    #[error("{message}")]
+    RestartRequired { message: String },
+    #[error("{message}")]
+    Cpu { message: String },
+    #[error("unknown parameter {0:?}")]
+    UnknownParameter(String),
+    #[error("invalid value {value:?} for parameter {name:?}")]
+    InvalidParameterValue { name: String, value: String },
+    #[error("parameters {0:?} cannot be changed at runtime")]
+    Reconfigure(Vec<&'static str>),
+    #[error("encoder already finished")]
+    Finished,
+    #[error("encoder worker disconnected")]
+    Disconnected,
+    #[error("I/O error: {message}")]
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    #[error("decoded picture hash mismatch for POC {poc}")]
+    PictureHashMismatch { poc: u64 },
+    #[error("no decoded picture hash for POC {poc}")]
+    MissingPictureHash { poc: u64 },
+    #[error("callback panicked: {message}")]
+    CallbackPanicked { message: String },
+    #[error("VVenC {loaded} is older than the {linked} headers it was built against")]
+    IncompatibleVersion { loaded: Version, linked: Version },
+    #[error("unknown error with code {code}: {message}")]
+    Unknown { code: i32, message: String },
 }
 
 impl Error {
     fn new(code: ErrorCodes) -> Self {
+        let message = unsafe { string_from_ptr(vvenc_get_error_msg(code)) };
+        Self::with_message(code, message)
+    }
+
+    // Prefers the detailed explanation VVenC keeps for the last failed call on `encoder`.
+    fn from_encoder(code: ErrorCodes, encoder: &InnerEncoder) -> Self {
+        match unsafe { string_from_ptr(vvenc_get_last_error(encoder.encoder.as_ptr())) } {
+            Some(message) => Self::with_message(code, Some(message)),
+            None => Self::new(code),
+        }
+    }
+
+    // VVenC logs which parameters it rejected instead of keeping them in its last error, so a
+    // failed open checks the config again and appends the errors logged there.
+    fn from_open(code: ErrorCodes, encoder: &InnerEncoder, config: &Config) -> Self {
+        let mut message =
+            unsafe { string_from_ptr(vvenc_get_last_error(encoder.encoder.as_ptr())) };
+        if let Err(diagnostics) = config.validate() {
+            let rejected = diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == LogLevel::Error)
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; ");
+            if !rejected.is_empty() {
+                message = Some(match message {
+                    Some(message) => format!("{message}: {rejected}"),
+                    None => rejected,
+                });
+            }
+        }
+        Self::with_message(code, message)
+    }
+
+    fn with_message(code: ErrorCodes, message: Option<String>) -> Self {
         #[allow(non_upper_case_globals)]
         match code {
-            ErrorCodes_VVENC_ERR_UNSPECIFIED => Error::Unspecified,
-            ErrorCodes_VVENC_ERR_INITIALIZE => Error::Initialize,
-            ErrorCodes_VVENC_ERR_ALLOCATE => Error::Allocate,
-            ErrorCodes_VVENC_NOT_ENOUGH_MEM => Error::NotEnoughMemory,
-            ErrorCodes_VVENC_ERR_PARAMETER => Error::Parameter,
-            ErrorCodes_VVENC_ERR_NOT_SUPPORTED => Error::NotSupported,
-            ErrorCodes_VVENC_ERR_RESTART_REQUIRED => Error::RestartRequired,
-            ErrorCodes_VVENC_ERR_CPU => Error::Cpu,
-            code => Error::Unknown(code),
+            ErrorCodes_VVENC_ERR_UNSPECIFIED => Error::Unspecified {
+                message: message.unwrap_or_else(|| "unspecified error".into()),
+            },
+            ErrorCodes_VVENC_ERR_INITIALIZE => Error::Initialize {
+                message: message.unwrap_or_else(|| "failed to initialize encoder".into()),
+            },
+            ErrorCodes_VVENC_ERR_ALLOCATE => Error::Allocate {
+                message: message.unwrap_or_else(|| "failed to allocate resources".into()),
+            },
+            ErrorCodes_VVENC_NOT_ENOUGH_MEM => Error::NotEnoughMemory {
+                message: message.unwrap_or_else(|| "not enough memory".into()),
+            },
+            ErrorCodes_VVENC_ERR_PARAMETER => Error::Parameter {
+                message: message.unwrap_or_else(|| "invalid parameter".into()),
+            },
+            ErrorCodes_VVENC_ERR_NOT_SUPPORTED => Error::NotSupported {
+                message: message.unwrap_or_else(|| "operation not supported".into()),
+            },
+            ErrorCodes_VVENC_ERR_RESTART_REQUIRED => Error::RestartRequired {
+                message: message.unwrap_or_else(|| "restart required".into()),
+            },
+            ErrorCodes_VVENC_ERR_CPU => Error::Cpu {
+                message: message.unwrap_or_else(|| "CPU error".into()),
+            },
+            code => Error::Unknown {
+                code,
+                message: message.unwrap_or_default(),
+            },
+        }
+    }
+
+    fn parameter(message: impl Into<String>) -> Self {
+        Error::Parameter {
+            message: message.into(),
+        }
+    }
+}
+
+// Reads a member behind a typed getter. Config setters reject values outside the enum (see
+// `Config::check_typed_members`) and VVenC only stores values it defines.
+fn from_ffi_checked<T>(value: Option<T>) -> T {
+    value.expect("typed vvenc_config members hold values defined by VVenC")
+}
+
+// Splits a `Config::set_params` list into trimmed name and value pairs.
+fn split_params(params: &str) -> Vec<(String, String)> {
+    let mut params_list = Vec::new();
+    let (mut name, mut value) = (String::new(), None::<String>);
+    let mut chars = params.chars();
+    loop {
+        let c = chars.next();
+        match c {
+            Some('\\') => {
+                let escaped = chars.next().unwrap_or('\\');
+                value.as_mut().unwrap_or(&mut name).push(escaped);
+            }
+            Some('=') if value.is_none() => value = Some(String::new()),
+            Some(':') | None => {
+                if !name.trim().is_empty() {
+                    let value = value.take().unwrap_or_else(|| "1".to_owned());
+                    params_list.push((name.trim().to_owned(), value.trim().to_owned()));
+                }
+                name.clear();
+                value = None;
+                if c.is_none() {
+                    return params_list;
+                }
+            }
+            Some(c) => value.as_mut().unwrap_or(&mut name).push(c),
+        }
+    }
+}
+
+// Copies a string owned by VVenC, treating null and empty strings as missing.
+unsafe fn string_from_ptr(ptr: *const std::os::raw::c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let string = std::ffi::CStr::from_ptr(ptr).to_string_lossy();
+    let string = string.trim_end();
+    (!string.is_empty()).then(|| string.to_owned())
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io {
+            kind: error.kind(),
+            message: error.to_string(),
         }
     }
 }
@@ -1047,6 +1670,14 @@ impl<'a, Opaque: Sized + Sync + Send> AccessUnit<'a, Opaque> {
         self.inner.poc
     }
 
+    pub fn has_buffering_period_sei(&self) -> bool {
+        sei::contains(self.payload(), sei::BUFFERING_PERIOD)
+    }
+
+    pub fn has_picture_timing_sei(&self) -> bool {
+        sei::contains(self.payload(), sei::PICTURE_TIMING)
+    }
+
     // pub fn take_opaque(&mut self) -> Box<Opaque> {
     //     let raw = self.inner.opaque;
     //     self.inner.opaque = ptr::null_mut();
@@ -1064,7 +1695,102 @@ impl<'a, Opaque: Sized + Sync + Send> AccessUnit<'a, Opaque> {
     }
 }
 
+#[derive(Debug)]
+pub struct Packet<Opaque> {
+    payload: Vec<u8>,
+    cts: Option<u64>,
+    dts: Option<u64>,
+    rap: bool,
+    slice_type: SliceType,
+    is_ref_pic: bool,
+    temporal_layer: i32,
+    poc: u64,
+    opaque: Option<Box<Opaque>>,
+}
+
+impl<Opaque> Packet<Opaque> {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Takes the payload, e.g. to hand it back through [`Encoder::recycle_payload`] once consumed.
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+
+    pub fn cts(&self) -> Option<u64> {
+        self.cts
+    }
+
+    pub fn dts(&self) -> Option<u64> {
+        self.dts
+    }
+
+    pub fn rap(&self) -> bool {
+        self.rap
+    }
+
+    pub fn slice_type(&self) -> SliceType {
+        self.slice_type
+    }
+
+    pub fn is_ref_pic(&self) -> bool {
+        self.is_ref_pic
+    }
+
+    pub fn temporal_layer(&self) -> i32 {
+        self.temporal_layer
+    }
+
+    pub fn poc(&self) -> u64 {
+        self.poc
+    }
+
+    pub fn has_buffering_period_sei(&self) -> bool {
+        sei::contains(&self.payload, sei::BUFFERING_PERIOD)
+    }
+
+    pub fn has_picture_timing_sei(&self) -> bool {
+        sei::contains(&self.payload, sei::PICTURE_TIMING)
+    }
+
+    pub fn take_opaque(&mut self) -> Option<Box<Opaque>> {
+        self.opaque.take()
+    }
+
+    pub fn set_opaque(&mut self, opaque: Box<Opaque>) {
+        self.opaque = Some(opaque);
+    }
+}
+
+impl<Opaque: Sized + Sync + Send> From<AccessUnit<'_, Opaque>> for Packet<Opaque> {
+    fn from(au: AccessUnit<'_, Opaque>) -> Self {
+        Self::with_payload(au, Vec::new())
+    }
+}
+
+impl<Opaque: Sized + Sync + Send> Packet<Opaque> {
+    // Copies the payload into `payload`, reusing its allocation when it is large enough.
+    fn with_payload(mut au: AccessUnit<'_, Opaque>, mut payload: Vec<u8>) -> Self {
+        payload.clear();
+        payload.extend_from_slice(au.payload());
+        Self {
+            payload,
+            cts: au.cts(),
+            dts: au.dts(),
+            rap: au.rap(),
+            slice_type: au.slice_type(),
+            is_ref_pic: au.is_ref_pic(),
+            temporal_layer: au.temporal_layer(),
+            poc: au.poc(),
+            opaque: au.take_opaque(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Preset {
     Faster,
     Fast,
@@ -1095,6 +1821,8 @@ impl Preset {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ChromaFormat {
     Chroma400,
     Chroma420,
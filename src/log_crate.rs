@@ -0,0 +1,69 @@
+use std::borrow::Cow;
+
+use crate::{Config, LogLevel, Logger};
+
+/// [`Logger`] forwarding VVenC messages to the [`log`] facade, one record per line.
+///
+/// VVenC only emits messages up to [`Config::log_level`], so it should be at least as verbose as
+/// the maximum level enabled in `log`.
+#[derive(Debug, Clone)]
+pub struct LogCrateLogger {
+    target: Cow<'static, str>,
+}
+
+impl LogCrateLogger {
+    pub fn new(target: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            target: target.into(),
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
+
+impl Default for LogCrateLogger {
+    fn default() -> Self {
+        Self::new("vvenc")
+    }
+}
+
+impl LogLevel {
+    fn to_log_level(self) -> Option<log::Level> {
+        match self {
+            Self::Silent => None,
+            Self::Error => Some(log::Level::Error),
+            Self::Warning => Some(log::Level::Warn),
+            Self::Info => Some(log::Level::Info),
+            Self::Notice | Self::Verbose => Some(log::Level::Debug),
+            Self::Details => Some(log::Level::Trace),
+        }
+    }
+}
+
+impl Logger for LogCrateLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        let Some(level) = level.to_log_level() else {
+            return;
+        };
+        if !log::log_enabled!(target: &self.target, level) {
+            return;
+        }
+        for line in message.lines().map(str::trim_end) {
+            if !line.is_empty() {
+                log::log!(target: &self.target, level, "{line}");
+            }
+        }
+    }
+}
+
+impl Config {
+    pub fn with_log_crate(&mut self) -> &mut Self {
+        self.set_logger(LogCrateLogger::default())
+    }
+
+    pub fn with_log_crate_target(&mut self, target: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.set_logger(LogCrateLogger::new(target))
+    }
+}
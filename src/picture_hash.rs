@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use vvenc_sys::*;
+
+use crate::{sei, AccessUnit, Config, Error, Packet, ReconFrame, YUVComponent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PictureHash {
+    Md5,
+    Crc,
+    Checksum,
+    None,
+}
+
+impl PictureHash {
+    #[inline]
+    fn to_ffi(self) -> vvencHashType {
+        match self {
+            Self::Md5 => vvencHashType_VVENC_HASHTYPE_MD5,
+            Self::Crc => vvencHashType_VVENC_HASHTYPE_CRC,
+            Self::Checksum => vvencHashType_VVENC_HASHTYPE_CHECKSUM,
+            Self::None => vvencHashType_VVENC_HASHTYPE_NONE,
+        }
+    }
+
+    #[allow(non_upper_case_globals)]
+    #[inline]
+    fn from_ffi(value: vvencHashType) -> Self {
+        match value {
+            vvencHashType_VVENC_HASHTYPE_MD5 => Self::Md5,
+            vvencHashType_VVENC_HASHTYPE_CRC => Self::Crc,
+            vvencHashType_VVENC_HASHTYPE_CHECKSUM => Self::Checksum,
+            _ => Self::None,
+        }
+    }
+
+    // Value of `dph_sei_hash_type` in the SEI message.
+    fn from_sei(value: u8) -> Self {
+        match value {
+            0 => Self::Md5,
+            1 => Self::Crc,
+            2 => Self::Checksum,
+            _ => Self::None,
+        }
+    }
+
+    fn digest_len(self) -> usize {
+        match self {
+            Self::Md5 => 16,
+            Self::Crc => 2,
+            Self::Checksum => 4,
+            Self::None => 0,
+        }
+    }
+}
+
+impl Config {
+    pub fn decoded_picture_hash(&self) -> PictureHash {
+        PictureHash::from_ffi(self.inner.m_decodedPictureHashSEIType)
+    }
+
+    pub fn set_decoded_picture_hash(&mut self, picture_hash: PictureHash) -> &mut Self {
+        self.inner.m_decodedPictureHashSEIType = picture_hash.to_ffi();
+        self
+    }
+}
+
+/// Checks the decoded picture hash SEI carried by each access unit against the matching
+/// reconstructed frame, paired by picture order count.
+///
+/// Frames and access units can be added in any order; each picture is verified as soon as both
+/// are known.
+#[derive(Debug)]
+pub struct PictureHashVerifier {
+    picture_hash: PictureHash,
+    bit_depth: [i32; 2],
+    recon: HashMap<u64, Vec<Vec<u8>>>,
+    embedded: HashMap<u64, (PictureHash, Vec<Vec<u8>>)>,
+    verified: usize,
+}
+
+impl PictureHashVerifier {
+    /// Uses the hash type and internal bit depth of `config`, which should be the configuration of
+    /// the opened encoder (see [`Encoder::config`](crate::Encoder::config)).
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        let picture_hash = config.decoded_picture_hash();
+        if picture_hash == PictureHash::None {
+            return Err(Error::parameter("decoded picture hash SEI is disabled"));
+        }
+        let [luma, chroma] = config.internal_bit_depth();
+        Ok(Self {
+            picture_hash,
+            bit_depth: [luma, if chroma > 0 { chroma } else { luma }],
+            recon: HashMap::new(),
+            embedded: HashMap::new(),
+            verified: 0,
+        })
+    }
+
+    pub fn add_recon_frame(&mut self, frame: &ReconFrame) -> Result<(), Error> {
+        let digests = [YUVComponent::Y, YUVComponent::U, YUVComponent::V]
+            .into_iter()
+            .map(|component| frame.plane(component))
+            .filter(|plane| plane.width() > 0 && plane.height() > 0)
+            .enumerate()
+            .map(|(i, mut plane)| {
+                let bit_depth = self.bit_depth[i.min(1)];
+                plane_digest(self.picture_hash, bit_depth, &mut plane)
+            })
+            .collect();
+        self.recon.insert(frame.poc(), digests);
+        self.check(frame.poc())
+    }
+
+    pub fn add_access_unit<Opaque: Sized + Sync + Send>(
+        &mut self,
+        access_unit: &AccessUnit<Opaque>,
+    ) -> Result<(), Error> {
+        self.add_payload(access_unit.poc(), access_unit.payload())
+    }
+
+    pub fn add_packet<Opaque>(&mut self, packet: &Packet<Opaque>) -> Result<(), Error> {
+        self.add_payload(packet.poc(), packet.payload())
+    }
+
+    fn add_payload(&mut self, poc: u64, payload: &[u8]) -> Result<(), Error> {
+        let embedded = sei::sei_messages(payload)
+            .into_iter()
+            .filter(|(payload_type, _)| *payload_type == sei::DECODED_PICTURE_HASH)
+            .find_map(|(_, payload)| decoded_picture_hash(&payload))
+            .ok_or(Error::MissingPictureHash { poc })?;
+        self.embedded.insert(poc, embedded);
+        self.check(poc)
+    }
+
+    fn check(&mut self, poc: u64) -> Result<(), Error> {
+        if !self.recon.contains_key(&poc) || !self.embedded.contains_key(&poc) {
+            return Ok(());
+        }
+        let recon = self.recon.remove(&poc);
+        let embedded = self.embedded.remove(&poc);
+        if embedded != recon.map(|recon| (self.picture_hash, recon)) {
+            return Err(Error::PictureHashMismatch { poc });
+        }
+        self.verified += 1;
+        Ok(())
+    }
+
+    /// Number of pictures whose hash matched so far.
+    pub fn verified(&self) -> usize {
+        self.verified
+    }
+
+    /// Fails if a reconstructed frame or an access unit was never matched.
+    pub fn finish(self) -> Result<usize, Error> {
+        match self.recon.keys().chain(self.embedded.keys()).min() {
+            Some(&poc) => Err(Error::MissingPictureHash { poc }),
+            None => Ok(self.verified),
+        }
+    }
+}
+
+// Returns the per-component digests of a decoded picture hash SEI payload.
+fn decoded_picture_hash(payload: &[u8]) -> Option<(PictureHash, Vec<Vec<u8>>)> {
+    let [hash_type, flags, digests @ ..] = payload else {
+        return None;
+    };
+    let picture_hash = PictureHash::from_sei(*hash_type);
+    let components = if flags & 0x80 != 0 { 1 } else { 3 };
+    let len = picture_hash.digest_len();
+    if len == 0 || digests.len() < components * len {
+        return None;
+    }
+    Some((
+        picture_hash,
+        digests
+            .chunks(len)
+            .take(components)
+            .map(<[u8]>::to_vec)
+            .collect(),
+    ))
+}
+
+// Digests follow the reference decoder: samples are hashed as one byte up to 8 bits and as two
+// little-endian bytes above.
+fn plane_digest(picture_hash: PictureHash, bit_depth: i32, plane: &mut crate::Plane) -> Vec<u8> {
+    let width = plane.width() as usize;
+    let height = plane.height() as usize;
+    let stride = plane.stride() as usize;
+    let wide = bit_depth > 8;
+    let rows = plane.data().chunks(stride).take(height);
+
+    match picture_hash {
+        PictureHash::Md5 => {
+            let mut context = md5::Context::new();
+            let mut bytes = Vec::with_capacity(width * 2);
+            for row in rows {
+                bytes.clear();
+                for &sample in &row[..width] {
+                    let [low, high] = (sample as u16).to_le_bytes();
+                    bytes.push(low);
+                    if wide {
+                        bytes.push(high);
+                    }
+                }
+                context.consume(&bytes);
+            }
+            context.compute().0.to_vec()
+        }
+        PictureHash::Crc => {
+            let mut crc = 0xFFFFu32;
+            let mut push_bits = |value: u32, bits: u32| {
+                for bit in (0..bits).rev() {
+                    let msb = (crc >> 15) & 1;
+                    crc = (((crc << 1) | ((value >> bit) & 1)) & 0xFFFF) ^ (msb * 0x1021);
+                }
+            };
+            for row in rows {
+                for &sample in &row[..width] {
+                    let sample = u32::from(sample as u16);
+                    push_bits(sample & 0xFF, 8);
+                    if wide {
+                        push_bits(sample >> 8, 8);
+                    }
+                }
+            }
+            push_bits(0, 16);
+            (crc as u16).to_be_bytes().to_vec()
+        }
+        PictureHash::Checksum => {
+            let mut checksum = 0u32;
+            for (y, row) in rows.enumerate() {
+                for (x, &sample) in row[..width].iter().enumerate() {
+                    let mask = ((x ^ y ^ (x >> 8) ^ (y >> 8)) & 0xFF) as u32;
+                    let sample = u32::from(sample as u16);
+                    checksum = checksum.wrapping_add((sample & 0xFF) ^ mask);
+                    if wide {
+                        checksum = checksum.wrapping_add((sample >> 8) ^ mask);
+                    }
+                }
+            }
+            checksum.to_be_bytes().to_vec()
+        }
+        PictureHash::None => Vec::new(),
+    }
+}
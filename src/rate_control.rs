@@ -0,0 +1,134 @@
+use std::num::NonZeroU32;
+
+use crate::{Config, Error, Qp};
+
+/// How the encoder trades quality for bitrate. Bitrates are in bits per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RateControl {
+    ConstantQp(Qp),
+    Vbr {
+        target_bitrate: NonZeroU32,
+        passes: Passes,
+        /// QP of the first frame, chosen by the encoder when `None`.
+        initial_qp: Option<Qp>,
+    },
+    CappedVbr {
+        target_bitrate: NonZeroU32,
+        max_bitrate: NonZeroU32,
+        passes: Passes,
+        initial_qp: Option<Qp>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Passes {
+    One,
+    Two,
+}
+
+impl Passes {
+    #[inline]
+    fn to_ffi(self) -> i32 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+
+    #[inline]
+    fn from_ffi(value: i32) -> Self {
+        match value {
+            2 => Self::Two,
+            _ => Self::One,
+        }
+    }
+}
+
+fn bitrate_to_ffi(bitrate: NonZeroU32) -> Result<i32, Error> {
+    i32::try_from(bitrate.get())
+        .map_err(|_| Error::parameter(format!("bitrate {bitrate} is out of range")))
+}
+
+fn initial_qp_to_ffi(initial_qp: Option<Qp>) -> i32 {
+    // VVenC treats an initial QP of 0 as "automatic".
+    initial_qp.map_or(0, Qp::to_ffi)
+}
+
+fn initial_qp_from_ffi(initial_qp: i32) -> Option<Qp> {
+    Qp::from_ffi(initial_qp).ok().filter(|_| initial_qp > 0)
+}
+
+impl Config {
+    /// Rate control derived from the QP, target and maximum bitrate fields: a zero target bitrate
+    /// means constant QP, a zero maximum bitrate means uncapped.
+    pub fn rate_control(&self) -> RateControl {
+        let passes = Passes::from_ffi(self.inner.m_RCNumPasses);
+        let initial_qp = initial_qp_from_ffi(self.inner.m_RCInitialQP);
+        let target_bitrate = u32::try_from(self.inner.m_RCTargetBitrate)
+            .ok()
+            .and_then(NonZeroU32::new);
+        let max_bitrate = u32::try_from(self.inner.m_RCMaxBitrate)
+            .ok()
+            .and_then(NonZeroU32::new);
+
+        match (target_bitrate, max_bitrate) {
+            (None, _) => RateControl::ConstantQp(self.qp()),
+            (Some(target_bitrate), None) => RateControl::Vbr {
+                target_bitrate,
+                passes,
+                initial_qp,
+            },
+            (Some(target_bitrate), Some(max_bitrate)) => RateControl::CappedVbr {
+                target_bitrate,
+                max_bitrate,
+                passes,
+                initial_qp,
+            },
+        }
+    }
+
+    /// Sets every rate control field at once, overriding `set_qp`, `set_target_bitrate` and
+    /// `set_num_passes`.
+    pub fn set_rate_control(&mut self, rate_control: RateControl) -> Result<&mut Self, Error> {
+        let (target_bitrate, max_bitrate, passes, initial_qp) = match rate_control {
+            RateControl::ConstantQp(qp) => {
+                self.inner.m_QP = qp.to_ffi();
+                (0, 0, Passes::One, None)
+            }
+            RateControl::Vbr {
+                target_bitrate,
+                passes,
+                initial_qp,
+            } => (bitrate_to_ffi(target_bitrate)?, 0, passes, initial_qp),
+            RateControl::CappedVbr {
+                target_bitrate,
+                max_bitrate,
+                passes,
+                initial_qp,
+            } => {
+                if max_bitrate < target_bitrate {
+                    return Err(Error::parameter(format!(
+                        "maximum bitrate {max_bitrate} is below target bitrate {target_bitrate}"
+                    )));
+                }
+                (
+                    bitrate_to_ffi(target_bitrate)?,
+                    bitrate_to_ffi(max_bitrate)?,
+                    passes,
+                    initial_qp,
+                )
+            }
+        };
+
+        self.inner.m_RCTargetBitrate = target_bitrate;
+        self.inner.m_RCMaxBitrate = max_bitrate;
+        self.inner.m_RCNumPasses = passes.to_ffi();
+        self.inner.m_RCPass = -1;
+        self.inner.m_RCInitialQP = initial_qp_to_ffi(initial_qp);
+        Ok(self)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::nal_units;
+
+const PREFIX_SEI_NUT: u8 = 23;
+const SUFFIX_SEI_NUT: u8 = 24;
+
+pub(crate) const BUFFERING_PERIOD: u32 = 0;
+pub(crate) const PICTURE_TIMING: u32 = 1;
+pub(crate) const DECODED_PICTURE_HASH: u32 = 132;
+
+// SEI messages of the prefix and suffix SEI NAL units in an Annex B payload, as their payload type
+// and unescaped payload.
+pub(crate) fn sei_messages(data: &[u8]) -> Vec<(u32, Vec<u8>)> {
+    let mut messages = Vec::new();
+    for nal in nal_units(data) {
+        if nal.len() > 2 && matches!(nal[1] >> 3, PREFIX_SEI_NUT | SUFFIX_SEI_NUT) {
+            parse_messages(&unescape(&nal[2..]), &mut messages);
+        }
+    }
+    messages
+}
+
+pub(crate) fn contains(data: &[u8], payload_type: u32) -> bool {
+    sei_messages(data)
+        .iter()
+        .any(|(message_type, _)| *message_type == payload_type)
+}
+
+// Removes the emulation prevention bytes of a NAL unit payload.
+fn unescape(nal: &[u8]) -> Vec<u8> {
+    let mut rbsp = Vec::with_capacity(nal.len());
+    let mut zeros = 0;
+    for &byte in nal {
+        if zeros >= 2 && byte == 3 {
+            zeros = 0;
+            continue;
+        }
+        zeros = if byte == 0 { zeros + 1 } else { 0 };
+        rbsp.push(byte);
+    }
+    rbsp
+}
+
+fn parse_messages(mut data: &[u8], messages: &mut Vec<(u32, Vec<u8>)>) {
+    // Each message starts with its type and size, coded as runs of 0xFF plus a final byte.
+    let read_value = |data: &mut &[u8]| {
+        let mut value = 0u32;
+        loop {
+            let (&byte, rest) = data.split_first()?;
+            *data = rest;
+            value += u32::from(byte);
+            if byte != 0xFF {
+                return Some(value);
+            }
+        }
+    };
+
+    while !data.is_empty() && data != [0x80] {
+        let (Some(payload_type), Some(payload_size)) =
+            (read_value(&mut data), read_value(&mut data))
+        else {
+            return;
+        };
+        let Some((payload, rest)) = data.split_at_checked(payload_size as usize) else {
+            return;
+        };
+        messages.push((payload_type, payload.to_vec()));
+        data = rest;
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use futures::{
+    channel::{mpsc, oneshot},
+    executor, Sink, Stream,
+};
+
+use crate::{worker, Config, Error, Packet, YUVBuffer};
+
+/// Spawns an encoder thread and returns the [`Sink`] accepting its frames along with the
+/// [`Stream`] yielding its packets. The blocking VVenC calls never run on the caller's executor.
+///
+/// Closing the sink flushes the encoder; dropping the stream cancels it.
+pub async fn encoder_stream<Opaque: Sized + Sync + Send + 'static>(
+    config: Config,
+    capacity: usize,
+) -> Result<(FrameSink<Opaque>, PacketStream<Opaque>), Error> {
+    let (frames_tx, frames_rx) = mpsc::channel(capacity);
+    let (packets_tx, packets_rx) = mpsc::unbounded();
+    let (init_tx, init_rx) = oneshot::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    worker::spawn(
+        config,
+        executor::block_on_stream(frames_rx),
+        move |packet| packets_tx.unbounded_send(packet).is_ok(),
+        move |result| {
+            let _ = init_tx.send(result);
+        },
+        cancelled.clone(),
+    )?;
+
+    init_rx.await.map_err(|_| Error::Disconnected)??;
+
+    Ok((
+        FrameSink {
+            frames: frames_tx.clone(),
+        },
+        PacketStream {
+            packets: packets_rx,
+            frames: frames_tx,
+            cancelled,
+        },
+    ))
+}
+
+#[derive(Debug)]
+pub struct FrameSink<Opaque> {
+    frames: mpsc::Sender<YUVBuffer<Opaque>>,
+}
+
+impl<Opaque> Sink<YUVBuffer<Opaque>> for FrameSink<Opaque> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut()
+            .frames
+            .poll_ready(cx)
+            .map_err(|_| Error::Disconnected)
+    }
+
+    fn start_send(self: Pin<&mut Self>, frame: YUVBuffer<Opaque>) -> Result<(), Error> {
+        self.get_mut()
+            .frames
+            .start_send(frame)
+            .map_err(|_| Error::Disconnected)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.get_mut().frames)
+            .poll_flush(cx)
+            .map_err(|_| Error::Disconnected)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.get_mut().frames)
+            .poll_close(cx)
+            .map_err(|_| Error::Disconnected)
+    }
+}
+
+#[derive(Debug)]
+pub struct PacketStream<Opaque> {
+    packets: mpsc::UnboundedReceiver<Result<Packet<Opaque>, Error>>,
+    // Only used to close the frame channel on drop, which wakes a worker waiting for frames
+    // that a still-alive sink may never send.
+    frames: mpsc::Sender<YUVBuffer<Opaque>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<Opaque> Stream for PacketStream<Opaque> {
+    type Item = Result<Packet<Opaque>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().packets).poll_next(cx)
+    }
+}
+
+impl<Opaque> Drop for PacketStream<Opaque> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.frames.close_channel();
+    }
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use tracing::Span;
+
+use crate::{Config, LogLevel, Logger};
+
+/// [`Logger`] emitting VVenC messages as [`tracing`] events inside a span, one event per line.
+///
+/// VVenC logs from its worker threads, so events are parented to the span explicitly rather than
+/// to the current span of the logging thread.
+#[derive(Debug, Clone)]
+pub struct TracingLogger {
+    span: Span,
+}
+
+impl TracingLogger {
+    pub fn new(span: Span) -> Self {
+        Self { span }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl Logger for TracingLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        let emit = || {
+            for line in message.lines().map(str::trim_end) {
+                if line.is_empty() {
+                    continue;
+                }
+                // The level of `tracing` events must be known at compile time.
+                match level {
+                    LogLevel::Silent => {}
+                    LogLevel::Error => tracing::error!(parent: &self.span, "{line}"),
+                    LogLevel::Warning => tracing::warn!(parent: &self.span, "{line}"),
+                    LogLevel::Info => tracing::info!(parent: &self.span, "{line}"),
+                    LogLevel::Notice | LogLevel::Verbose => {
+                        tracing::debug!(parent: &self.span, "{line}")
+                    }
+                    LogLevel::Details => tracing::trace!(parent: &self.span, "{line}"),
+                }
+            }
+        };
+        // Worker threads may not have the subscriber the span was created with as their default.
+        match self.span.with_subscriber(|(_, dispatch)| dispatch.clone()) {
+            Some(dispatch) => tracing::dispatcher::with_default(&dispatch, emit),
+            None => emit(),
+        }
+    }
+}
+
+impl Config {
+    /// Attaches `span` to encoders opened with this configuration: VVenC messages are emitted as
+    /// events inside it, along with a timing event for every encode and flush call.
+    ///
+    /// Fields identifying the encoder, such as a job id or rendition, belong on the span:
+    ///
+    /// ```ignore
+    /// config.with_tracing(tracing::info_span!("encoder", job_id = 42, rendition = "1080p"));
+    /// ```
+    pub fn with_tracing(&mut self, span: Span) -> &mut Self {
+        self.set_logger(TracingLogger::new(span.clone()));
+        self.span = Some(span);
+        self
+    }
+
+    pub fn tracing_span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+}
+
+pub(crate) fn encode_event(span: &Span, flush: bool, elapsed: Duration, bytes: usize) {
+    let elapsed_us = elapsed.as_micros() as u64;
+    if flush {
+        tracing::debug!(parent: span, elapsed_us, bytes, "flush");
+    } else {
+        tracing::debug!(parent: span, elapsed_us, bytes, "encode");
+    }
+}
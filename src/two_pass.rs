@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use tempfile::TempPath;
+
+use crate::{Config, Encoder, Error, Packet, YUVBuffer};
+
+/// Rate-controlled encoder running an analysis pass followed by the final pass.
+///
+/// Both passes share the same VVenC encoder instance and a statistics file, which is either
+/// a temporary file removed on drop or a user-provided path that is left in place.
+#[derive(Debug)]
+pub struct TwoPassEncoder<Opaque> {
+    encoder: Encoder<Opaque>,
+    stats_file: StatsFile,
+    // VVenC cannot restart the passes once they ran, so `encode` only runs them once.
+    started: bool,
+}
+
+#[derive(Debug)]
+enum StatsFile {
+    Temporary(TempPath),
+    User(PathBuf),
+}
+
+impl StatsFile {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Temporary(path) => path,
+            Self::User(path) => path,
+        }
+    }
+}
+
+impl<Opaque: Sized + Sync + Send> TwoPassEncoder<Opaque> {
+    pub fn with_config(config: Config) -> Result<Self, Error> {
+        let stats_file = tempfile::Builder::new()
+            .prefix("vvenc-stats-")
+            .suffix(".json")
+            .tempfile()?
+            .into_temp_path();
+        Self::with_stats_file_inner(config, StatsFile::Temporary(stats_file))
+    }
+
+    pub fn with_stats_file(config: Config, stats_file: impl Into<PathBuf>) -> Result<Self, Error> {
+        Self::with_stats_file_inner(config, StatsFile::User(stats_file.into()))
+    }
+
+    fn with_stats_file_inner(mut config: Config, stats_file: StatsFile) -> Result<Self, Error> {
+        config.set_num_passes(2).set_pass(-1);
+        Ok(Self {
+            encoder: Encoder::with_config(config)?,
+            stats_file,
+            started: false,
+        })
+    }
+
+    pub fn stats_file(&self) -> &Path {
+        self.stats_file.path()
+    }
+
+    pub fn config(&self) -> Config {
+        self.encoder.config()
+    }
+
+    /// Runs the analysis pass over the frames yielded by `frames`, then returns the packets of
+    /// the final pass, which is encoded as they are pulled. `frames` is called once per pass and
+    /// must produce the same sequence each time.
+    ///
+    /// The passes can only be run once; later calls return [`Error::Finished`].
+    pub fn encode<F, I>(
+        &mut self,
+        mut frames: F,
+    ) -> Result<TwoPassPackets<'_, Opaque, I::IntoIter>, Error>
+    where
+        F: FnMut() -> I,
+        I: IntoIterator<Item = YUVBuffer<Opaque>>,
+    {
+        if self.started {
+            return Err(Error::Finished);
+        }
+        self.started = true;
+        self.encoder.init_pass(0, self.stats_file.path())?;
+        for packet in TwoPassPackets::new(&mut self.encoder, frames().into_iter()) {
+            packet?;
+        }
+
+        self.encoder.init_pass(1, self.stats_file.path())?;
+        Ok(TwoPassPackets::new(&mut self.encoder, frames().into_iter()))
+    }
+}
+
+/// Packets of the final pass of a [`TwoPassEncoder`], encoding the remaining frames on demand and
+/// flushing the encoder once they run out.
+#[derive(Debug)]
+pub struct TwoPassPackets<'a, Opaque, I> {
+    encoder: &'a mut Encoder<Opaque>,
+    frames: I,
+    failed: bool,
+}
+
+impl<'a, Opaque, I> TwoPassPackets<'a, Opaque, I> {
+    fn new(encoder: &'a mut Encoder<Opaque>, frames: I) -> Self {
+        Self {
+            encoder,
+            frames,
+            failed: false,
+        }
+    }
+}
+
+impl<Opaque, I> Iterator for TwoPassPackets<'_, Opaque, I>
+where
+    Opaque: Sized + Sync + Send,
+    I: Iterator<Item = YUVBuffer<Opaque>>,
+{
+    type Item = Result<Packet<Opaque>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed && !self.encoder.is_finished() {
+            let ret = match self.frames.next() {
+                Some(mut frame) => self.encoder.encode_raw_packet(Some(&mut frame)),
+                None => self.encoder.encode_raw_packet(None),
+            };
+            match ret {
+                Ok((Some(packet), _)) => return Some(Ok(packet)),
+                Ok((None, _)) => continue,
+                Err(error) => {
+                    self.failed = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+        None
+    }
+}
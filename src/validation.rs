@@ -0,0 +1,176 @@
+use std::{
+    ops::Deref,
+    ptr,
+    sync::{Arc, Mutex},
+};
+
+use vvenc_sys::*;
+
+use crate::{Config, Error, LogLevel, Logger};
+
+/// A [`Config`] accepted by `vvenc_check_config`, along with the warnings raised while checking it.
+#[derive(Debug, Clone)]
+pub struct ValidatedConfig {
+    config: Config,
+    warnings: Vec<ConfigDiagnostic>,
+}
+
+impl ValidatedConfig {
+    pub fn warnings(&self) -> &[ConfigDiagnostic] {
+        &self.warnings
+    }
+
+    pub fn into_inner(self) -> Config {
+        self.config
+    }
+}
+
+impl Deref for ValidatedConfig {
+    type Target = Config;
+
+    fn deref(&self) -> &Config {
+        &self.config
+    }
+}
+
+impl From<ValidatedConfig> for Config {
+    fn from(config: ValidatedConfig) -> Self {
+        config.config
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDiagnostic {
+    pub severity: LogLevel,
+    pub message: String,
+    /// Name of the [`Config`] accessor the message refers to, when it can be recognized.
+    pub field: Option<&'static str>,
+}
+
+impl ConfigDiagnostic {
+    fn new(severity: LogLevel, message: &str) -> Self {
+        let message = message.trim_end().to_owned();
+        let field = recognize_field(&message);
+        Self {
+            severity,
+            message,
+            field,
+        }
+    }
+}
+
+// Keywords used by VVenC when reporting a parameter, mapped to the matching Config accessor.
+const FIELD_KEYWORDS: &[(&[&str], &str)] = &[
+    (&["sourcewidth", "width"], "width"),
+    (&["sourceheight", "height"], "height"),
+    (&["framerate", "framescale", "fps"], "framerate"),
+    (&["tickspersecond"], "ticks_per_second"),
+    (&["framestobeencoded"], "frames_to_be_encoded"),
+    (&["inputbitdepth"], "input_bit_depth"),
+    (&["outputbitdepth"], "output_bit_depth"),
+    (&["internalbitdepth", "bitdepth"], "internal_bit_depth"),
+    (&["threads", "numthreads"], "num_threads"),
+    (&["qp"], "qp"),
+    (&["targetbitrate", "bitrate"], "target_bitrate"),
+    (&["profile"], "profile"),
+    (&["tier"], "tier"),
+    (&["level"], "level"),
+    (
+        &["intraperiod", "intraperiodsec", "refreshsec"],
+        "intra_period",
+    ),
+    (&["decodingrefreshtype"], "decoding_refresh_type"),
+    (&["gopsize", "gop"], "gop_size"),
+    (&["passes", "numpasses"], "num_passes"),
+    (&["pass"], "pass"),
+    (&["hdr", "hdrmode"], "hdr_mode"),
+    (&["colourprimaries", "colorprimaries"], "colour_primaries"),
+    (&["transfercharacteristics"], "transfer_characteristics"),
+    (
+        &["matrixcoefficients", "matrixcoeffs"],
+        "matrix_coefficients",
+    ),
+    (&["videofullrange", "videofullrangeflag"], "full_range"),
+    (&["chromasampleloctype"], "chroma_sample_location"),
+    (&["segment"], "segment_mode"),
+    (&["perceptqpa", "qpa"], "use_percept_qpa"),
+    (&["tilecolumns", "numtilecols"], "num_tile_columns"),
+    (&["tilerows", "numtilerows"], "num_tile_rows"),
+    (
+        &["chromaformat", "internchromaformat"],
+        "internal_chroma_format",
+    ),
+];
+
+fn recognize_field(message: &str) -> Option<&'static str> {
+    let words: Vec<String> = message
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .map(|word| {
+            word.trim_start_matches("m_")
+                .replace('_', "")
+                .to_ascii_lowercase()
+        })
+        .collect();
+    FIELD_KEYWORDS
+        .iter()
+        .find(|(keywords, _)| {
+            keywords
+                .iter()
+                .any(|keyword| words.iter().any(|word| word == keyword))
+        })
+        .map(|(_, field)| *field)
+}
+
+struct DiagnosticCollector(Arc<Mutex<Vec<ConfigDiagnostic>>>);
+
+impl Logger for DiagnosticCollector {
+    fn log(&self, level: LogLevel, message: &str) {
+        if matches!(level, LogLevel::Error | LogLevel::Warning) && !message.trim().is_empty() {
+            if let Ok(mut diagnostics) = self.0.lock() {
+                diagnostics.push(ConfigDiagnostic::new(level, message));
+            }
+        }
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<ValidatedConfig, Vec<ConfigDiagnostic>> {
+        let diagnostics = Arc::new(Mutex::new(Vec::new()));
+
+        let mut checked = self.clone();
+        if checked.log_level() < LogLevel::Warning {
+            checked.set_log_level(LogLevel::Warning);
+        }
+        checked.set_logger(DiagnosticCollector(diagnostics.clone()));
+
+        let ret = match ptr::NonNull::new(unsafe { vvenc_encoder_create() }) {
+            Some(encoder) => unsafe {
+                let ret = vvenc_check_config(encoder.as_ptr(), &checked.inner);
+                vvenc_encoder_close(encoder.as_ptr());
+                ret
+            },
+            None => ErrorCodes_VVENC_ERR_INITIALIZE,
+        };
+        let mut diagnostics =
+            std::mem::take(&mut *diagnostics.lock().unwrap_or_else(|e| e.into_inner()));
+        diagnostics.extend(self.colour_description_diagnostics());
+        diagnostics.extend(self.hdr_metadata_diagnostics());
+        diagnostics.extend(self.hrd_diagnostics());
+        let failed = diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == LogLevel::Error);
+
+        if ret == ErrorCodes_VVENC_OK && !failed {
+            return Ok(ValidatedConfig {
+                config: self.clone(),
+                warnings: diagnostics,
+            });
+        }
+
+        if !failed {
+            let error = Error::new(ret);
+            diagnostics.push(ConfigDiagnostic::new(LogLevel::Error, &error.to_string()));
+        }
+        Err(diagnostics)
+    }
+}
@@ -0,0 +1,83 @@
+use std::{fmt, str::FromStr};
+
+use vvenc_sys::*;
+
+use crate::{string_from_ptr, Error};
+
+/// Version of the libvvenc headers this crate was built against.
+pub const LINKED_VERSION: Version = Version::new(
+    VVENC_LINKED_VERSION_MAJOR,
+    VVENC_LINKED_VERSION_MINOR,
+    VVENC_LINKED_VERSION_PATCH,
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    // Accepts a missing patch number and ignores suffixes such as "-rc1" or "+g1234abc".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::parameter(format!("invalid VVenC version {s:?}"));
+        let core = s
+            .trim()
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()
+            .unwrap_or_default();
+        let mut numbers = core.split('.').map(str::parse::<u32>);
+        let major = numbers.next().and_then(Result::ok).ok_or_else(invalid)?;
+        let minor = numbers.next().and_then(Result::ok).ok_or_else(invalid)?;
+        let patch = match numbers.next() {
+            Some(patch) => patch.map_err(|_| invalid())?,
+            None => 0,
+        };
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Version of the libvvenc loaded at runtime.
+pub fn version() -> Result<Version, Error> {
+    unsafe { string_from_ptr(vvenc_get_version()) }
+        .unwrap_or_default()
+        .parse()
+}
+
+/// Compiler, architecture and SIMD details of the loaded libvvenc build.
+pub fn compile_info() -> String {
+    unsafe { string_from_ptr(vvenc_get_compile_info_string()) }.unwrap_or_default()
+}
+
+/// Fails if the loaded libvvenc is older than the headers this crate was built against, which
+/// could make the layout of `vvenc_config` disagree.
+///
+/// Opening an [`Encoder`](crate::Encoder) performs this check.
+pub fn check_version() -> Result<Version, Error> {
+    let loaded = version()?;
+    let linked = LINKED_VERSION;
+    if loaded < linked {
+        return Err(Error::IncompatibleVersion { loaded, linked });
+    }
+    Ok(loaded)
+}
@@ -0,0 +1,414 @@
+use std::fmt;
+
+use crate::{Config, ConfigDiagnostic, HdrMode, LogLevel};
+
+// Colour description code points as defined by ITU-T H.273.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ColourPrimaries {
+    Bt709,
+    Unspecified,
+    Bt470M,
+    Bt470Bg,
+    Bt601,
+    Smpte240,
+    Film,
+    Bt2020,
+    Smpte428,
+    Smpte431,
+    Smpte432,
+    Ebu3213,
+    Unknown(i32),
+}
+
+impl ColourPrimaries {
+    #[inline]
+    fn to_ffi(self) -> i32 {
+        match self {
+            Self::Bt709 => 1,
+            Self::Unspecified => 2,
+            Self::Bt470M => 4,
+            Self::Bt470Bg => 5,
+            Self::Bt601 => 6,
+            Self::Smpte240 => 7,
+            Self::Film => 8,
+            Self::Bt2020 => 9,
+            Self::Smpte428 => 10,
+            Self::Smpte431 => 11,
+            Self::Smpte432 => 12,
+            Self::Ebu3213 => 22,
+            Self::Unknown(value) => value,
+        }
+    }
+
+    #[inline]
+    fn from_ffi(value: i32) -> Self {
+        match value {
+            1 => Self::Bt709,
+            2 => Self::Unspecified,
+            4 => Self::Bt470M,
+            5 => Self::Bt470Bg,
+            6 => Self::Bt601,
+            7 => Self::Smpte240,
+            8 => Self::Film,
+            9 => Self::Bt2020,
+            10 => Self::Smpte428,
+            11 => Self::Smpte431,
+            12 => Self::Smpte432,
+            22 => Self::Ebu3213,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TransferCharacteristics {
+    Bt709,
+    Unspecified,
+    Bt470M,
+    Bt470Bg,
+    Bt601,
+    Smpte240,
+    Linear,
+    Log100,
+    Log100Sqrt10,
+    Iec61966_2_4,
+    Bt1361,
+    Srgb,
+    Bt2020_10,
+    Bt2020_12,
+    Pq,
+    Smpte428,
+    Hlg,
+    Unknown(i32),
+}
+
+impl TransferCharacteristics {
+    #[inline]
+    fn to_ffi(self) -> i32 {
+        match self {
+            Self::Bt709 => 1,
+            Self::Unspecified => 2,
+            Self::Bt470M => 4,
+            Self::Bt470Bg => 5,
+            Self::Bt601 => 6,
+            Self::Smpte240 => 7,
+            Self::Linear => 8,
+            Self::Log100 => 9,
+            Self::Log100Sqrt10 => 10,
+            Self::Iec61966_2_4 => 11,
+            Self::Bt1361 => 12,
+            Self::Srgb => 13,
+            Self::Bt2020_10 => 14,
+            Self::Bt2020_12 => 15,
+            Self::Pq => 16,
+            Self::Smpte428 => 17,
+            Self::Hlg => 18,
+            Self::Unknown(value) => value,
+        }
+    }
+
+    #[inline]
+    fn from_ffi(value: i32) -> Self {
+        match value {
+            1 => Self::Bt709,
+            2 => Self::Unspecified,
+            4 => Self::Bt470M,
+            5 => Self::Bt470Bg,
+            6 => Self::Bt601,
+            7 => Self::Smpte240,
+            8 => Self::Linear,
+            9 => Self::Log100,
+            10 => Self::Log100Sqrt10,
+            11 => Self::Iec61966_2_4,
+            12 => Self::Bt1361,
+            13 => Self::Srgb,
+            14 => Self::Bt2020_10,
+            15 => Self::Bt2020_12,
+            16 => Self::Pq,
+            17 => Self::Smpte428,
+            18 => Self::Hlg,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MatrixCoefficients {
+    Identity,
+    Bt709,
+    Unspecified,
+    Fcc,
+    Bt470Bg,
+    Bt601,
+    Smpte240,
+    YCgCo,
+    Bt2020Ncl,
+    Bt2020Cl,
+    Smpte2085,
+    ChromaDerivedNcl,
+    ChromaDerivedCl,
+    ICtCp,
+    Unknown(i32),
+}
+
+impl MatrixCoefficients {
+    #[inline]
+    fn to_ffi(self) -> i32 {
+        match self {
+            Self::Identity => 0,
+            Self::Bt709 => 1,
+            Self::Unspecified => 2,
+            Self::Fcc => 4,
+            Self::Bt470Bg => 5,
+            Self::Bt601 => 6,
+            Self::Smpte240 => 7,
+            Self::YCgCo => 8,
+            Self::Bt2020Ncl => 9,
+            Self::Bt2020Cl => 10,
+            Self::Smpte2085 => 11,
+            Self::ChromaDerivedNcl => 12,
+            Self::ChromaDerivedCl => 13,
+            Self::ICtCp => 14,
+            Self::Unknown(value) => value,
+        }
+    }
+
+    #[inline]
+    fn from_ffi(value: i32) -> Self {
+        match value {
+            0 => Self::Identity,
+            1 => Self::Bt709,
+            2 => Self::Unspecified,
+            4 => Self::Fcc,
+            5 => Self::Bt470Bg,
+            6 => Self::Bt601,
+            7 => Self::Smpte240,
+            8 => Self::YCgCo,
+            9 => Self::Bt2020Ncl,
+            10 => Self::Bt2020Cl,
+            11 => Self::Smpte2085,
+            12 => Self::ChromaDerivedNcl,
+            13 => Self::ChromaDerivedCl,
+            14 => Self::ICtCp,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ChromaSampleLocation {
+    Left,
+    Center,
+    TopLeft,
+    Top,
+    BottomLeft,
+    Bottom,
+    Unknown(i32),
+}
+
+impl ChromaSampleLocation {
+    #[inline]
+    fn to_ffi(self) -> i32 {
+        match self {
+            Self::Left => 0,
+            Self::Center => 1,
+            Self::TopLeft => 2,
+            Self::Top => 3,
+            Self::BottomLeft => 4,
+            Self::Bottom => 5,
+            Self::Unknown(value) => value,
+        }
+    }
+
+    #[inline]
+    fn from_ffi(value: i32) -> Self {
+        match value {
+            0 => Self::Left,
+            1 => Self::Center,
+            2 => Self::TopLeft,
+            3 => Self::Top,
+            4 => Self::BottomLeft,
+            5 => Self::Bottom,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+// Colour description values consistent with an HDR mode, as VVenC fills them in when left
+// unspecified. HLG is signalled either directly or as BT.2020 with HLG as the preferred transfer
+// characteristics, and the modes without a colour space only constrain the transfer function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ColourExpectation {
+    primaries: &'static [ColourPrimaries],
+    transfer: &'static [TransferCharacteristics],
+    matrix: &'static [MatrixCoefficients],
+}
+
+impl HdrMode {
+    fn colour_expectation(self) -> Option<ColourExpectation> {
+        use ColourPrimaries as P;
+        use MatrixCoefficients as M;
+        use TransferCharacteristics as T;
+
+        let (primaries, transfer, matrix): (&[_], &[_], &[_]) = match self {
+            Self::Off | Self::UserDefined => return None,
+            Self::Pq => (&[], &[T::Pq], &[]),
+            Self::PqBt2020 => (&[P::Bt2020], &[T::Pq], &[M::Bt2020Ncl]),
+            Self::Hlg => (&[], &[T::Hlg, T::Bt2020_10], &[]),
+            Self::HlgBt2020 => (&[P::Bt2020], &[T::Hlg, T::Bt2020_10], &[M::Bt2020Ncl]),
+            Self::SdrBt709 => (&[P::Bt709], &[T::Bt709], &[M::Bt709]),
+            Self::SdrBt2020 => (&[P::Bt2020], &[T::Bt2020_10, T::Bt2020_12], &[M::Bt2020Ncl]),
+            Self::SdrBt470bg => (
+                &[P::Bt470Bg],
+                &[T::Bt470Bg, T::Bt601],
+                &[M::Bt470Bg, M::Bt601],
+            ),
+        };
+        Some(ColourExpectation {
+            primaries,
+            transfer,
+            matrix,
+        })
+    }
+}
+
+impl Config {
+    pub fn colour_primaries(&self) -> ColourPrimaries {
+        ColourPrimaries::from_ffi(self.inner.m_colourPrimaries)
+    }
+
+    pub fn set_colour_primaries(&mut self, colour_primaries: ColourPrimaries) -> &mut Self {
+        self.inner.m_colourPrimaries = colour_primaries.to_ffi();
+        self.enable_colour_description()
+    }
+
+    pub fn transfer_characteristics(&self) -> TransferCharacteristics {
+        TransferCharacteristics::from_ffi(self.inner.m_transferCharacteristics)
+    }
+
+    pub fn set_transfer_characteristics(
+        &mut self,
+        transfer_characteristics: TransferCharacteristics,
+    ) -> &mut Self {
+        self.inner.m_transferCharacteristics = transfer_characteristics.to_ffi();
+        self.enable_colour_description()
+    }
+
+    pub fn matrix_coefficients(&self) -> MatrixCoefficients {
+        MatrixCoefficients::from_ffi(self.inner.m_matrixCoefficients)
+    }
+
+    pub fn set_matrix_coefficients(
+        &mut self,
+        matrix_coefficients: MatrixCoefficients,
+    ) -> &mut Self {
+        self.inner.m_matrixCoefficients = matrix_coefficients.to_ffi();
+        self.enable_colour_description()
+    }
+
+    pub fn full_range(&self) -> bool {
+        self.inner.m_videoFullRangeFlag
+    }
+
+    pub fn set_full_range(&mut self, full_range: bool) -> &mut Self {
+        self.inner.m_videoFullRangeFlag = full_range;
+        self.inner.m_videoSignalTypePresent = true;
+        self.inner.m_vuiParametersPresent = 1;
+        self
+    }
+
+    pub fn chroma_sample_location(&self) -> ChromaSampleLocation {
+        ChromaSampleLocation::from_ffi(self.inner.m_chromaSampleLocType)
+    }
+
+    pub fn set_chroma_sample_location(
+        &mut self,
+        chroma_sample_location: ChromaSampleLocation,
+    ) -> &mut Self {
+        let value = chroma_sample_location.to_ffi();
+        self.inner.m_chromaSampleLocType = value;
+        self.inner.m_chromaSampleLocTypeTopField = value;
+        self.inner.m_chromaSampleLocTypeBottomField = value;
+        self.inner.m_chromaLocInfoPresent = true;
+        self.inner.m_vuiParametersPresent = 1;
+        self
+    }
+
+    fn enable_colour_description(&mut self) -> &mut Self {
+        self.inner.m_colourDescriptionPresent = true;
+        self.inner.m_videoSignalTypePresent = true;
+        self.inner.m_vuiParametersPresent = 1;
+        self
+    }
+
+    // An explicit colour description must agree with the one implied by the HDR mode, since VVenC
+    // would otherwise silently signal one of them. Unspecified values are filled in by VVenC.
+    pub(crate) fn colour_description_diagnostics(&self) -> Vec<ConfigDiagnostic> {
+        let hdr_mode = self.hdr_mode();
+        let Some(expectation) = hdr_mode.colour_expectation() else {
+            return Vec::new();
+        };
+        if !self.inner.m_colourDescriptionPresent {
+            return Vec::new();
+        }
+
+        [
+            colour_diagnostic(
+                "colour_primaries",
+                hdr_mode,
+                self.colour_primaries(),
+                ColourPrimaries::Unspecified,
+                expectation.primaries,
+            ),
+            colour_diagnostic(
+                "transfer_characteristics",
+                hdr_mode,
+                self.transfer_characteristics(),
+                TransferCharacteristics::Unspecified,
+                expectation.transfer,
+            ),
+            colour_diagnostic(
+                "matrix_coefficients",
+                hdr_mode,
+                self.matrix_coefficients(),
+                MatrixCoefficients::Unspecified,
+                expectation.matrix,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+fn colour_diagnostic<T: Copy + PartialEq + fmt::Debug>(
+    field: &'static str,
+    hdr_mode: HdrMode,
+    actual: T,
+    unspecified: T,
+    expected: &[T],
+) -> Option<ConfigDiagnostic> {
+    if expected.is_empty() || actual == unspecified || expected.contains(&actual) {
+        return None;
+    }
+    let expected = match expected {
+        [expected] => format!("{expected:?}"),
+        expected => format!("one of {expected:?}"),
+    };
+    Some(ConfigDiagnostic {
+        severity: LogLevel::Error,
+        message: format!(
+            "{field} {actual:?} is inconsistent with HDR mode {hdr_mode:?}, which requires {expected}"
+        ),
+        field: Some(field),
+    })
+}
@@ -0,0 +1,156 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{Config, Encoder, Error, Packet, YUVBuffer};
+
+/// Runs an [`Encoder`] on a dedicated thread.
+///
+/// Frames are queued through a bounded channel, so [`EncoderWorker::send`] blocks once `capacity`
+/// frames are pending. Packets and errors are delivered in order through [`EncoderWorker::recv`];
+/// the worker stops after reporting the first error.
+#[derive(Debug)]
+pub struct EncoderWorker<Opaque> {
+    frames: Option<SyncSender<YUVBuffer<Opaque>>>,
+    packets: Receiver<Result<Packet<Opaque>, Error>>,
+    cancelled: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<Opaque: Sized + Sync + Send + 'static> EncoderWorker<Opaque> {
+    pub fn spawn(config: Config, capacity: usize) -> Result<Self, Error> {
+        let (frames_tx, frames_rx) = mpsc::sync_channel(capacity);
+        let (packets_tx, packets_rx) = mpsc::channel();
+        let (init_tx, init_rx) = mpsc::sync_channel(1);
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let handle = spawn(
+            config,
+            frames_rx,
+            move |packet| packets_tx.send(packet).is_ok(),
+            move |result| {
+                let _ = init_tx.send(result);
+            },
+            cancelled.clone(),
+        )?;
+
+        init_rx.recv().map_err(|_| Error::Disconnected)??;
+
+        Ok(Self {
+            frames: Some(frames_tx),
+            packets: packets_rx,
+            cancelled,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn send(&self, frame: YUVBuffer<Opaque>) -> Result<(), Error> {
+        self.frames
+            .as_ref()
+            .ok_or(Error::Finished)?
+            .send(frame)
+            .map_err(|_| Error::Disconnected)
+    }
+
+    /// Blocks until the next packet or error is available. Returns `None` once the worker has
+    /// stopped and every packet has been received.
+    pub fn recv(&self) -> Option<Result<Packet<Opaque>, Error>> {
+        self.packets.recv().ok()
+    }
+
+    pub fn try_recv(&self) -> Option<Result<Packet<Opaque>, Error>> {
+        self.packets.try_recv().ok()
+    }
+
+    pub fn packets(&self) -> &Receiver<Result<Packet<Opaque>, Error>> {
+        &self.packets
+    }
+
+    /// Closes the input: the worker encodes the queued frames, drains the encoder and stops.
+    pub fn flush(&mut self) {
+        self.frames = None;
+    }
+
+    /// Stops the worker, discarding queued frames and pending output, after which
+    /// [`EncoderWorker::recv`] returns `None`.
+    ///
+    /// Blocks until the worker thread has exited, which takes at most the encode call in flight.
+    pub fn cancel(&mut self) {
+        self.stop();
+        while self.packets.try_recv().is_ok() {}
+    }
+}
+
+impl<Opaque> EncoderWorker<Opaque> {
+    fn stop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.frames = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl<Opaque> Drop for EncoderWorker<Opaque> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Opens the encoder on a new thread, reports the outcome through `on_init` and then encodes
+// `frames` until they run out, `emit` returns false or `cancelled` is set.
+pub(crate) fn spawn<Opaque, F, E, I>(
+    config: Config,
+    frames: F,
+    mut emit: E,
+    on_init: I,
+    cancelled: Arc<AtomicBool>,
+) -> Result<JoinHandle<()>, Error>
+where
+    Opaque: Sized + Sync + Send + 'static,
+    F: IntoIterator<Item = YUVBuffer<Opaque>> + Send + 'static,
+    E: FnMut(Result<Packet<Opaque>, Error>) -> bool + Send + 'static,
+    I: FnOnce(Result<(), Error>) + Send + 'static,
+{
+    let handle = thread::Builder::new()
+        .name("vvenc-worker".into())
+        .spawn(move || {
+            let mut encoder = match Encoder::with_config(config) {
+                Ok(encoder) => {
+                    on_init(Ok(()));
+                    encoder
+                }
+                Err(error) => {
+                    on_init(Err(error));
+                    return;
+                }
+            };
+
+            for mut frame in frames {
+                if cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+                let packet = match encoder.encode_packet(&mut frame) {
+                    Ok(Some(packet)) => Ok(packet),
+                    Ok(None) => continue,
+                    Err(error) => Err(error),
+                };
+                let failed = packet.is_err();
+                if !emit(packet) || failed {
+                    return;
+                }
+            }
+
+            for packet in encoder.drain() {
+                if cancelled.load(Ordering::Relaxed) || !emit(packet) {
+                    return;
+                }
+            }
+        })?;
+    Ok(handle)
+}
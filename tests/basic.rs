@@ -1,5 +1,17 @@
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
 use vvenc::*;
 
+mod common;
+
+use common::{config, CHROMA_FORMAT, HEIGHT, WIDTH};
+
 struct BasicLogger;
 
 impl Logger for BasicLogger {
@@ -10,21 +22,10 @@ impl Logger for BasicLogger {
 
 #[test]
 fn basic() {
-    const WIDTH: i32 = 160;
-    const HEIGHT: i32 = 120;
-    const CHROMA_FORMAT: ChromaFormat = ChromaFormat::Chroma420;
-
-    let mut config = Config::default();
+    let mut config = config();
     config
-        .set_width(WIDTH)
-        .set_height(HEIGHT)
-        .set_framerate(Rational { num: 30, den: 1 })
-        .set_qp(Qp::new(32).unwrap())
-        .set_internal_chroma_format(CHROMA_FORMAT)
         .set_log_level(LogLevel::Details)
-        .set_logger(Box::new(BasicLogger))
-        .set_preset(Preset::Faster)
-        .unwrap();
+        .set_logger(BasicLogger);
 
     let mut encoder = Encoder::with_config(config).unwrap();
     let mut data = vec![0u8; (2 * WIDTH * HEIGHT + 1024) as usize];
@@ -69,5 +70,766 @@ fn basic() {
     assert!(encode_done);
     assert_eq!(*au.take_opaque().unwrap(), 1234u64);
     assert_eq!(au.cts().unwrap(), 0);
-    assert!(encoder.flush(&mut data).unwrap_err() == Error::RestartRequired);
+    assert!(encoder.flush(&mut data).unwrap_err() == Error::Finished);
+}
+
+#[test]
+fn logger_ownership() {
+    struct CountingLogger {
+        messages: Arc<AtomicUsize>,
+    }
+
+    impl Logger for CountingLogger {
+        fn log(&self, _level: LogLevel, _message: &str) {
+            self.messages.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let replaced = Arc::new(AtomicUsize::new(0));
+    let messages = Arc::new(AtomicUsize::new(0));
+    let mut config = config();
+    config
+        .set_log_level(LogLevel::Details)
+        .set_logger(CountingLogger {
+            messages: replaced.clone(),
+        });
+    assert_eq!(Arc::strong_count(&replaced), 2);
+
+    // Replacing the logger releases the previous one.
+    config.set_logger(CountingLogger {
+        messages: messages.clone(),
+    });
+    assert_eq!(Arc::strong_count(&replaced), 1);
+    assert_eq!(Arc::strong_count(&messages), 2);
+
+    // The encoder keeps the logger alive after every config referencing it is gone.
+    let cloned = config.clone();
+    drop(config);
+    let mut encoder = Encoder::<()>::with_config(cloned.clone()).unwrap();
+    drop(cloned);
+    assert_eq!(Arc::strong_count(&messages), 2);
+
+    let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+    buffer.set_cts(0);
+    encoder.encode_packet(&mut buffer).unwrap();
+    for packet in encoder.drain() {
+        packet.unwrap();
+    }
+    assert!(messages.load(Ordering::Relaxed) > 0);
+    assert_eq!(replaced.load(Ordering::Relaxed), 0);
+
+    drop(encoder);
+    assert_eq!(Arc::strong_count(&messages), 1);
+
+    let lines = Arc::new(AtomicUsize::new(0));
+    let mut config = Config::default();
+    config.set_logger({
+        let lines = lines.clone();
+        move |_level: LogLevel, message: &str| {
+            lines.fetch_add(message.lines().count(), Ordering::Relaxed);
+        }
+    });
+    config.set_width(-1);
+    assert!(config.validate().is_err());
+    drop(config);
+    assert_eq!(Arc::strong_count(&lines), 1);
+}
+
+#[test]
+fn callback_panics() {
+    let config = config();
+
+    let encode = |config: Config, recon_panics: bool| {
+        let mut encoder = Encoder::<()>::with_config(config)?;
+        if recon_panics {
+            encoder.set_recon_handler(|_| panic!("recon handler panicked"));
+        }
+        let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+        buffer.set_cts(0);
+        encoder.encode_packet(&mut buffer)?;
+        encoder.drain().collect::<Result<Vec<_>, _>>()
+    };
+
+    let mut panicking = config.clone();
+    panicking
+        .set_log_level(LogLevel::Details)
+        .set_logger(|_: LogLevel, _: &str| panic!("logger panicked"));
+    assert_eq!(
+        encode(panicking, false).unwrap_err(),
+        Error::CallbackPanicked {
+            message: "logger panicked".into()
+        }
+    );
+
+    assert_eq!(
+        encode(config.clone(), true).unwrap_err(),
+        Error::CallbackPanicked {
+            message: "recon handler panicked".into()
+        }
+    );
+
+    assert_eq!(encode(config.clone(), false).unwrap().len(), 1);
+
+    // A panic while opening fails is reported there, not by the next encoder sharing the logger.
+    let panicked = Arc::new(AtomicBool::new(false));
+    let mut failing = config;
+    failing
+        .set_log_level(LogLevel::Details)
+        .set_logger(move |_: LogLevel, _: &str| {
+            if !panicked.swap(true, Ordering::Relaxed) {
+                panic!("logger panicked");
+            }
+        });
+    let valid = failing.clone();
+    failing.set_width(-1);
+    assert_eq!(
+        Encoder::<()>::with_config(failing).unwrap_err(),
+        Error::CallbackPanicked {
+            message: "logger panicked".into()
+        }
+    );
+    assert!(Encoder::<()>::with_config(valid).is_ok());
+}
+
+#[test]
+fn packets() {
+    let config = config();
+
+    let mut encoder = Encoder::with_config(config).unwrap();
+    let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+
+    buffer.set_cts(0);
+    buffer.set_opaque(1234u64);
+    assert!(encoder.encode_packet(&mut buffer).unwrap().is_none());
+
+    buffer.set_cts(1);
+    buffer.set_opaque(5678u64);
+    assert!(encoder.encode_packet(&mut buffer).unwrap().is_none());
+
+    let packets: Vec<_> = encoder.drain().collect::<Result<_, _>>().unwrap();
+    assert!(encoder.is_finished());
+    assert!(encoder.drain().next().is_none());
+    assert_eq!(
+        encoder.encode_packet(&mut buffer).unwrap_err(),
+        Error::Finished
+    );
+
+    let packets = std::thread::spawn(move || packets).join().unwrap();
+    assert_eq!(packets.len(), 2);
+    let opaques: Vec<_> = packets
+        .into_iter()
+        .map(|mut packet| {
+            assert!(!packet.payload().is_empty());
+            (packet.cts().unwrap(), *packet.take_opaque().unwrap())
+        })
+        .collect();
+    assert_eq!(opaques, vec![(1, 5678), (0, 1234)]);
+}
+
+#[test]
+fn small_packet_buffer() {
+    const FRAMES: u64 = 8;
+
+    let mut config = config();
+    config.set_qp(Qp::new(0).unwrap());
+
+    let mut encoder = Encoder::<()>::with_config(config).unwrap();
+    // Far smaller than any access unit, so it must be grown before VVenC writes to it.
+    encoder.set_packet_buffer(vec![0; 16]);
+    let payload = Vec::with_capacity(1 << 20);
+    let recycled = payload.as_ptr();
+    encoder.recycle_payload(payload);
+
+    let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+    let mut packets = Vec::new();
+    for cts in 0..FRAMES {
+        // Noise, which compresses poorly at QP 0.
+        let mut state = cts as u32 + 1;
+        for component in [YUVComponent::Y, YUVComponent::U, YUVComponent::V] {
+            for sample in buffer.plane_mut(component).data_mut() {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                *sample = (state >> 22) as i16;
+            }
+        }
+        buffer.set_cts(cts);
+        packets.extend(encoder.encode_packet(&mut buffer).unwrap());
+    }
+    for packet in encoder.drain() {
+        packets.push(packet.unwrap());
+    }
+
+    assert_eq!(packets.len() as u64, FRAMES);
+    let mut cts: Vec<_> = packets.iter().map(|packet| packet.cts().unwrap()).collect();
+    cts.sort_unstable();
+    assert_eq!(cts, (0..FRAMES).collect::<Vec<_>>());
+    assert!(packets.iter().any(|packet| packet.payload().len() > 16));
+
+    // The first packet fills the recycled buffer rather than a new allocation.
+    assert_eq!(packets[0].payload().as_ptr(), recycled);
+}
+
+#[test]
+fn reconfigure() {
+    let config = config();
+
+    let mut encoder = Encoder::<()>::with_config(config).unwrap();
+    assert_eq!(
+        encoder
+            .reconfigure(|config| {
+                config.set_width(320).set_qp(Qp::new(30).unwrap());
+            })
+            .unwrap_err(),
+        Error::Reconfigure(vec!["width"])
+    );
+    assert_eq!(encoder.config().width(), WIDTH);
+    assert_eq!(
+        encoder
+            .reconfigure(|config| {
+                config.set_num_ref_pics(config.num_ref_pics() + 1);
+            })
+            .unwrap_err(),
+        Error::Reconfigure(vec!["num_ref_pics"])
+    );
+
+    encoder
+        .reconfigure(|config| {
+            config.set_qp(Qp::new(30).unwrap());
+        })
+        .unwrap();
+    assert_eq!(encoder.config().qp(), Qp::new(30).unwrap());
+}
+
+#[test]
+fn recon_handler() {
+    let config = config();
+
+    let mut encoder = Encoder::<()>::with_config(config).unwrap();
+    let recon_pocs = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    encoder.set_recon_handler({
+        let recon_pocs = recon_pocs.clone();
+        move |frame| {
+            let mut y = frame.plane(YUVComponent::Y);
+            assert_eq!(y.width(), WIDTH);
+            assert_eq!(y.height(), HEIGHT);
+            assert!(y.data().len() >= (WIDTH * HEIGHT) as usize);
+            recon_pocs.borrow_mut().push(frame.poc());
+        }
+    });
+
+    let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+    for cts in 0..2 {
+        buffer.set_cts(cts);
+        encoder.encode_packet(&mut buffer).unwrap();
+    }
+    for packet in encoder.drain() {
+        packet.unwrap();
+    }
+
+    let mut recon_pocs = recon_pocs.borrow().clone();
+    recon_pocs.sort();
+    assert_eq!(recon_pocs, vec![0, 1]);
+}
+
+#[test]
+fn picture_hash() {
+    for picture_hash in [PictureHash::Md5, PictureHash::Crc, PictureHash::Checksum] {
+        let mut config = config();
+        config.set_decoded_picture_hash(picture_hash);
+        assert_eq!(config.decoded_picture_hash(), picture_hash);
+
+        let mut encoder = Encoder::<()>::with_config(config).unwrap();
+        let verifier = std::rc::Rc::new(std::cell::RefCell::new(
+            PictureHashVerifier::new(&encoder.config()).unwrap(),
+        ));
+        let recon_results = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        encoder.set_recon_handler({
+            let verifier = verifier.clone();
+            let recon_results = recon_results.clone();
+            move |frame| {
+                let result = verifier.borrow_mut().add_recon_frame(frame);
+                recon_results.borrow_mut().push(result);
+            }
+        });
+
+        let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+        for cts in 0..3 {
+            buffer.set_cts(cts);
+            if let Some(packet) = encoder.encode_packet(&mut buffer).unwrap() {
+                verifier.borrow_mut().add_packet(&packet).unwrap();
+            }
+        }
+        for packet in encoder.drain() {
+            verifier.borrow_mut().add_packet(&packet.unwrap()).unwrap();
+        }
+        drop(encoder);
+
+        for result in recon_results.borrow_mut().drain(..) {
+            result.unwrap();
+        }
+        let verifier = std::rc::Rc::try_unwrap(verifier).unwrap().into_inner();
+        assert_eq!(verifier.finish().unwrap(), 3);
+    }
+}
+
+#[test]
+fn hrd_signalling() {
+    let mut config = config();
+    config
+        .set_buffering_period_sei(true)
+        .set_picture_timing_sei(true)
+        .set_access_unit_delimiter(Some(true));
+    let diagnostics = config.validate().unwrap_err();
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.field == Some("hrd_parameters")));
+
+    config
+        .set_hrd_parameters(true)
+        .set_rate_control(RateControl::Vbr {
+            target_bitrate: NonZeroU32::new(500_000).unwrap(),
+            passes: Passes::One,
+            initial_qp: None,
+        })
+        .unwrap();
+    let mut encoder = Encoder::<()>::with_config(config.validate().unwrap().into()).unwrap();
+
+    let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+    let mut packets = Vec::new();
+    for cts in 0..3 {
+        buffer.set_cts(cts);
+        packets.extend(encoder.encode_packet(&mut buffer).unwrap());
+    }
+    packets.extend(encoder.drain().map(Result::unwrap));
+
+    assert_eq!(packets.len(), 3);
+    for packet in &packets {
+        // The access unit delimiter (AUD_NUT 20) comes first.
+        let payload = packet.payload();
+        let start = payload.windows(3).position(|w| w == [0, 0, 1]).unwrap() + 3;
+        assert_eq!(payload[start + 1] >> 3, 20);
+        assert!(packet.has_picture_timing_sei());
+    }
+    assert!(packets
+        .iter()
+        .filter(|packet| packet.rap())
+        .all(|packet| packet.has_buffering_period_sei()));
+}
+
+#[test]
+fn headers() {
+    let config = config();
+
+    let mut encoder = Encoder::<()>::with_config(config).unwrap();
+    let headers = encoder.headers().unwrap();
+    assert!(headers.starts_with(&[0, 0, 0, 1]) || headers.starts_with(&[0, 0, 1]));
+
+    const SPS_NUT: u8 = 15;
+    const PPS_NUT: u8 = 16;
+    let nal_unit_types: Vec<_> = encoder
+        .header_nal_units()
+        .unwrap()
+        .iter()
+        .map(|nal| nal[1] >> 3)
+        .collect();
+    assert!(nal_unit_types.contains(&SPS_NUT));
+    assert!(nal_unit_types.contains(&PPS_NUT));
+}
+
+#[test]
+fn error_message() {
+    assert_eq!(
+        Qp::new(64).unwrap_err(),
+        Error::Parameter {
+            message: "QP 64 is out of range [0, 63]".into()
+        }
+    );
+
+    // VVenC's explanation names the rejected parameter, both when checking the config and when
+    // opening an encoder with it.
+    let mut config = Config::default();
+    config.set_width(-1).set_height(120);
+    let diagnostics = config.validate().unwrap_err();
+    assert!(diagnostics.iter().any(|diagnostic| {
+        diagnostic.severity == LogLevel::Error
+            && diagnostic.message.to_lowercase().contains("width")
+    }));
+    let error = Encoder::<()>::with_config(config).unwrap_err();
+    assert!(
+        error.to_string().to_lowercase().contains("width"),
+        "{error}"
+    );
+}
+
+#[test]
+fn validate() {
+    let mut config = config();
+
+    let validated = config.validate().unwrap();
+    assert!(validated
+        .warnings()
+        .iter()
+        .all(|diagnostic| diagnostic.severity == LogLevel::Warning));
+    Encoder::<()>::with_config(validated.into()).unwrap();
+
+    config.set_width(-1);
+    let diagnostics = config.validate().unwrap_err();
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == LogLevel::Error));
+}
+
+#[test]
+fn colour_description() {
+    let mut config = config();
+    config
+        .set_hdr_mode(HdrMode::SdrBt709)
+        .set_colour_primaries(ColourPrimaries::Bt709)
+        .set_transfer_characteristics(TransferCharacteristics::Bt709)
+        .set_matrix_coefficients(MatrixCoefficients::Bt709)
+        .set_full_range(true)
+        .set_chroma_sample_location(ChromaSampleLocation::Left);
+
+    assert_eq!(config.colour_primaries(), ColourPrimaries::Bt709);
+    assert_eq!(
+        config.transfer_characteristics(),
+        TransferCharacteristics::Bt709
+    );
+    assert_eq!(config.matrix_coefficients(), MatrixCoefficients::Bt709);
+    assert!(config.full_range());
+    assert_eq!(config.chroma_sample_location(), ChromaSampleLocation::Left);
+    config.validate().unwrap();
+
+    config.set_hdr_mode(HdrMode::PqBt2020);
+    let diagnostics = config.validate().unwrap_err();
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.field == Some("colour_primaries")));
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.field == Some("transfer_characteristics")));
+}
+
+// Colour description members flagged as inconsistent with the HDR mode.
+fn colour_description_errors(config: &Config) -> Vec<&'static str> {
+    let diagnostics = match config.validate() {
+        Ok(validated) => validated.warnings().to_vec(),
+        Err(diagnostics) => diagnostics,
+    };
+    diagnostics
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == LogLevel::Error)
+        .filter_map(|diagnostic| diagnostic.field)
+        .filter(|field| {
+            [
+                "colour_primaries",
+                "transfer_characteristics",
+                "matrix_coefficients",
+            ]
+            .contains(field)
+        })
+        .collect()
+}
+
+#[test]
+fn colour_description_hdr_modes() {
+    use ColourPrimaries as P;
+    use MatrixCoefficients as M;
+    use TransferCharacteristics as T;
+
+    #[rustfmt::skip]
+    let cases = [
+        (HdrMode::Off, (P::Bt709, T::Pq, M::Bt2020Ncl), vec![]),
+        (HdrMode::UserDefined, (P::Bt709, T::Pq, M::Bt2020Ncl), vec![]),
+        (HdrMode::Pq, (P::Bt2020, T::Pq, M::Bt2020Ncl), vec![]),
+        (HdrMode::Pq, (P::Bt709, T::Pq, M::Bt709), vec![]),
+        (HdrMode::Pq, (P::Bt2020, T::Hlg, M::Bt2020Ncl), vec!["transfer_characteristics"]),
+        (HdrMode::PqBt2020, (P::Bt2020, T::Pq, M::Bt2020Ncl), vec![]),
+        (HdrMode::PqBt2020, (P::Unspecified, T::Pq, M::Unspecified), vec![]),
+        (HdrMode::PqBt2020, (P::Bt709, T::Pq, M::Bt709), vec!["colour_primaries", "matrix_coefficients"]),
+        (HdrMode::Hlg, (P::Bt709, T::Bt2020_10, M::Bt709), vec![]),
+        (HdrMode::Hlg, (P::Bt709, T::Pq, M::Bt709), vec!["transfer_characteristics"]),
+        (HdrMode::HlgBt2020, (P::Bt2020, T::Hlg, M::Bt2020Ncl), vec![]),
+        (HdrMode::HlgBt2020, (P::Bt2020, T::Bt709, M::Bt709), vec!["transfer_characteristics", "matrix_coefficients"]),
+        (HdrMode::SdrBt709, (P::Bt709, T::Bt709, M::Bt709), vec![]),
+        (HdrMode::SdrBt709, (P::Bt2020, T::Bt709, M::Bt709), vec!["colour_primaries"]),
+        (HdrMode::SdrBt2020, (P::Bt2020, T::Bt2020_10, M::Bt2020Ncl), vec![]),
+        (HdrMode::SdrBt2020, (P::Bt2020, T::Pq, M::Bt2020Ncl), vec!["transfer_characteristics"]),
+        (HdrMode::SdrBt470bg, (P::Bt470Bg, T::Bt601, M::Bt470Bg), vec![]),
+        (HdrMode::SdrBt470bg, (P::Bt470Bg, T::Bt601, M::Bt709), vec!["matrix_coefficients"]),
+    ];
+
+    for (hdr_mode, (primaries, transfer, matrix), expected) in cases {
+        let mut config = config();
+        config
+            .set_internal_bit_depth([10, 10])
+            .set_hdr_mode(hdr_mode)
+            .set_colour_primaries(primaries)
+            .set_transfer_characteristics(transfer)
+            .set_matrix_coefficients(matrix);
+        assert_eq!(
+            colour_description_errors(&config),
+            expected,
+            "{hdr_mode:?} with {primaries:?}, {transfer:?}, {matrix:?}"
+        );
+    }
+}
+
+#[test]
+fn hdr_metadata() {
+    let syntax = "G(13250,34500)B(7500,3000)R(34000,16000)WP(15635,16450)L(10000000,1)";
+    let mastering_display: MasteringDisplay = syntax.parse().unwrap();
+    assert_eq!(
+        mastering_display.primaries[0],
+        Chromaticity { x: 0.68, y: 0.32 }
+    );
+    assert_eq!(mastering_display.max_luminance, 1000.0);
+    assert_eq!(mastering_display.min_luminance, 0.0001);
+    assert_eq!(mastering_display.to_string(), syntax);
+    assert!(
+        "G(13250,34500)B(7500,3000)R(34000,16000)WP(15635,16450)L(1,10)"
+            .parse::<MasteringDisplay>()
+            .is_err()
+    );
+    assert!("G(13250,34500)B(7500,3000)"
+        .parse::<MasteringDisplay>()
+        .is_err());
+
+    let content_light_level: ContentLightLevel = "1000,400".parse().unwrap();
+    assert_eq!(content_light_level.max_content_light_level, 1000);
+    assert_eq!(content_light_level.max_frame_average_light_level, 400);
+    assert!("400,1000".parse::<ContentLightLevel>().is_err());
+
+    let mut config = Config::default();
+    assert_eq!(config.mastering_display(), None);
+    config
+        .set_hdr_mode(HdrMode::PqBt2020)
+        .set_mastering_display(Some(mastering_display))
+        .unwrap()
+        .set_content_light_level(Some(content_light_level))
+        .unwrap();
+    assert_eq!(config.mastering_display(), Some(mastering_display));
+    assert_eq!(config.content_light_level(), Some(content_light_level));
+
+    config.set_mastering_display(None).unwrap();
+    assert_eq!(config.mastering_display(), None);
+}
+
+#[test]
+fn rate_control() {
+    let mut config = Config::default();
+    config.set_qp(Qp::new(30).unwrap()).set_target_bitrate(0);
+    assert_eq!(
+        config.rate_control(),
+        RateControl::ConstantQp(Qp::new(30).unwrap())
+    );
+
+    let capped = RateControl::CappedVbr {
+        target_bitrate: NonZeroU32::new(500_000).unwrap(),
+        max_bitrate: NonZeroU32::new(750_000).unwrap(),
+        passes: Passes::Two,
+        initial_qp: Some(Qp::new(32).unwrap()),
+    };
+    config.set_rate_control(capped).unwrap();
+    assert_eq!(config.rate_control(), capped);
+    assert_eq!(config.target_bitrate(), 500_000);
+    assert_eq!(config.num_passes(), 2);
+
+    let vbr = RateControl::Vbr {
+        target_bitrate: NonZeroU32::new(500_000).unwrap(),
+        passes: Passes::One,
+        initial_qp: None,
+    };
+    config.set_rate_control(vbr).unwrap();
+    assert_eq!(config.rate_control(), vbr);
+
+    assert!(config
+        .set_rate_control(RateControl::CappedVbr {
+            target_bitrate: NonZeroU32::new(500_000).unwrap(),
+            max_bitrate: NonZeroU32::new(250_000).unwrap(),
+            passes: Passes::One,
+            initial_qp: None,
+        })
+        .is_err());
+    assert_eq!(config.rate_control(), vbr);
+
+    config
+        .set_rate_control(RateControl::ConstantQp(Qp::new(27).unwrap()))
+        .unwrap();
+    assert_eq!(config.qp(), Qp::new(27).unwrap());
+    assert_eq!(config.target_bitrate(), 0);
+}
+
+#[test]
+fn config_fields() {
+    let mut config = Config::default();
+    config
+        .set_lookahead(Some(false))
+        .set_slice_type_adaptation(None)
+        .set_wpp(true)
+        .set_max_parallel_frames(2)
+        .set_alf(false)
+        .set_ccalf(false)
+        .set_sao(true)
+        .set_mctf(ToolMode::Off)
+        .set_tmvp(ToolMode::Automatic)
+        .set_lmcs(ScreenContentMode::On)
+        .set_rdoq(FastMode::Fast)
+        .set_mctf_unit_size(16)
+        .set_trace_file("trace.txt")
+        .unwrap();
+
+    assert_eq!(config.lookahead(), Some(false));
+    assert_eq!(config.slice_type_adaptation(), None);
+    assert!(config.wpp());
+    assert_eq!(config.max_parallel_frames(), 2);
+    assert!(!config.alf());
+    assert!(!config.ccalf());
+    assert!(config.sao());
+    assert_eq!(config.mctf(), ToolMode::Off);
+    assert_eq!(config.tmvp(), ToolMode::Automatic);
+    assert_eq!(config.lmcs(), ScreenContentMode::On);
+    assert_eq!(config.rdoq(), FastMode::Fast);
+    assert_eq!(config.mctf_unit_size(), 16);
+    assert_eq!(config.trace_file(), "trace.txt");
+    assert!(config.set_trace_file(&"x".repeat(8192)).is_err());
+
+    // Only members without a meaningful value, such as nested struct arrays, lack an accessor.
+    let unwrapped: Vec<_> = Config::unwrapped_fields().collect();
+    for field in [
+        "m_SourceWidth",
+        "m_LookAhead",
+        "m_alf",
+        "m_vvencMCTF.MCTF",
+        "m_vvencMCTF.MCTFUnitSize",
+        "m_traceFile",
+        "msgFnc",
+    ] {
+        assert!(!unwrapped.contains(&field), "{field} is not wrapped");
+    }
+    assert!(unwrapped.len() < 5, "{unwrapped:?}");
+}
+
+#[test]
+fn set_params() {
+    let mut config = Config::default();
+    config.set_params("qp=30:threads=2").unwrap();
+    assert_eq!(config.qp(), Qp::new(30).unwrap());
+    assert_eq!(config.num_threads(), 2);
+
+    assert_eq!(
+        config.set_param("NoSuchParameter", "1").unwrap_err(),
+        Error::UnknownParameter("NoSuchParameter".into())
+    );
+    assert_eq!(
+        config.set_params("qp=31:qp=abc").unwrap_err(),
+        Error::InvalidParameterValue {
+            name: "qp".into(),
+            value: "abc".into()
+        }
+    );
+    // A rejected list leaves the config as it was.
+    assert_eq!(config.qp(), Qp::new(30).unwrap());
+
+    assert_eq!(
+        config
+            .set_params("threads=4:No\\:Such\\=Parameter=1")
+            .unwrap_err(),
+        Error::UnknownParameter("No:Such=Parameter".into())
+    );
+    assert_eq!(config.num_threads(), 2);
+}
+
+#[test]
+fn set_param_out_of_range_enum() {
+    let mut config = Config::default();
+    config.set_log_level(LogLevel::Warning);
+    assert_eq!(
+        config.set_param("Verbosity", "9").unwrap_err(),
+        Error::InvalidParameterValue {
+            name: "Verbosity".into(),
+            value: "9".into()
+        }
+    );
+    assert_eq!(config.log_level(), LogLevel::Warning);
+
+    assert!(config.apply_cfg_str("m_verbosity : 9").is_err());
+    assert_eq!(config.log_level(), LogLevel::Warning);
+}
+
+#[test]
+fn cfg_round_trip() {
+    let mut config = Config::default();
+    config
+        .set_width(1280)
+        .set_height(720)
+        .set_framerate(Rational { num: 50, den: 1 })
+        .set_qp(Qp::new(27).unwrap())
+        .set_level(Level::Level4_1)
+        .set_tier(Tier::High)
+        .set_decoding_refresh_type(DecodingRefreshType::Idr)
+        .set_num_tile_columns(2)
+        .set_num_tile_rows(1)
+        .set_lookahead(Some(false))
+        .set_mctf_speed(3)
+        .set_trace_file("trace.txt")
+        .unwrap()
+        .set_content_light_level(Some("1000,400".parse().unwrap()))
+        .unwrap()
+        .set_mctf_unit_size(16);
+
+    // The profile only uses vvencapp options; members vvencapp has no option for are kept apart.
+    let cfg = config.to_cfg_string();
+    for key in [
+        "LookAhead",
+        "MCTFSpeed",
+        "TraceFile",
+        "MaxContentLightLevel",
+    ] {
+        assert!(cfg.lines().any(|line| line.starts_with(key)), "{key}");
+    }
+    assert!(!cfg.lines().any(|line| line.starts_with("m_")));
+    let members = config.to_member_cfg_string();
+    assert!(members.contains("m_vvencMCTF.MCTFUnitSize"));
+
+    let mut parsed = Config::from_cfg_str(&cfg).unwrap();
+    parsed.apply_cfg_str(&members).unwrap();
+    assert_eq!(parsed.to_cfg_string(), cfg);
+    assert_eq!(parsed.to_member_cfg_string(), members);
+    assert_eq!(parsed.mctf_unit_size(), 16);
+    assert_eq!(parsed.lookahead(), Some(false));
+    assert_eq!(parsed.mctf_speed(), 3);
+    assert_eq!(parsed.trace_file(), "trace.txt");
+    assert_eq!(parsed.content_light_level(), config.content_light_level());
+    assert_eq!(parsed.width(), 1280);
+    assert_eq!(parsed.framerate(), Rational { num: 50, den: 1 });
+    assert_eq!(parsed.qp(), Qp::new(27).unwrap());
+    assert_eq!(parsed.level(), Level::Level4_1);
+    assert_eq!(parsed.tier(), Tier::High);
+    assert_eq!(parsed.decoding_refresh_type(), DecodingRefreshType::Idr);
+
+    let parsed = Config::from_cfg_str(
+        "# encoding profile\n\
+         SourceWidth : 640   # luma samples\n\
+         \n\
+         SourceHeight: 360\n",
+    )
+    .unwrap();
+    assert_eq!((parsed.width(), parsed.height()), (640, 360));
+    assert!(Config::from_cfg_str("SourceWidth 640").is_err());
+}
+
+#[test]
+fn version() {
+    let loaded = vvenc::version().unwrap();
+    assert!(loaded >= LINKED_VERSION);
+    assert_eq!(check_version(), Ok(loaded));
+    assert!(!compile_info().is_empty());
+
+    assert_eq!("1.13.0".parse(), Ok(Version::new(1, 13, 0)));
+    assert_eq!("1.14".parse(), Ok(Version::new(1, 14, 0)));
+    assert_eq!("1.12.1-rc1".parse(), Ok(Version::new(1, 12, 1)));
+    assert_eq!(Version::new(1, 13, 0).to_string(), "1.13.0");
+    assert!("".parse::<Version>().is_err());
+    assert!("v1".parse::<Version>().is_err());
+    assert!(Version::new(1, 9, 0) < Version::new(1, 13, 0));
 }
@@ -0,0 +1,19 @@
+use vvenc::*;
+
+pub const WIDTH: i32 = 160;
+pub const HEIGHT: i32 = 120;
+pub const CHROMA_FORMAT: ChromaFormat = ChromaFormat::Chroma420;
+
+// A small, fast to encode constant-QP config shared by the tests.
+pub fn config() -> Config {
+    let mut config = Config::default();
+    config
+        .set_width(WIDTH)
+        .set_height(HEIGHT)
+        .set_framerate(Rational { num: 30, den: 1 })
+        .set_qp(Qp::new(32).unwrap())
+        .set_internal_chroma_format(CHROMA_FORMAT)
+        .set_preset(Preset::Faster)
+        .unwrap();
+    config
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "log")]
+
+use std::sync::Mutex;
+
+use vvenc::*;
+
+mod common;
+
+use common::{config, CHROMA_FORMAT, HEIGHT, WIDTH};
+
+static RECORDS: Mutex<Vec<(log::Level, String, String)>> = Mutex::new(Vec::new());
+
+struct Capture;
+
+impl log::Log for Capture {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        RECORDS.lock().unwrap().push((
+            record.level(),
+            record.target().to_owned(),
+            record.args().to_string(),
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn log_crate() {
+    log::set_logger(&Capture).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let logger = LogCrateLogger::default();
+    Logger::log(&logger, LogLevel::Warning, "first line\nsecond line  \n\n");
+    Logger::log(&logger, LogLevel::Silent, "dropped\n");
+    assert_eq!(
+        std::mem::take(&mut *RECORDS.lock().unwrap()),
+        vec![
+            (log::Level::Warn, "vvenc".into(), "first line".into()),
+            (log::Level::Warn, "vvenc".into(), "second line".into()),
+        ]
+    );
+
+    let mut config = config();
+    config
+        .set_log_level(LogLevel::Details)
+        .with_log_crate_target("encoder");
+
+    let mut encoder = Encoder::<()>::with_config(config).unwrap();
+    let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+    buffer.set_cts(0);
+    encoder.encode_packet(&mut buffer).unwrap();
+    for packet in encoder.drain() {
+        packet.unwrap();
+    }
+
+    let records = RECORDS.lock().unwrap();
+    assert!(!records.is_empty());
+    assert!(records
+        .iter()
+        .all(|(_, target, message)| target == "encoder"
+            && !message.is_empty()
+            && !message.contains('\n')));
+}
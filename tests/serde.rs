@@ -0,0 +1,93 @@
+#![cfg(feature = "serde")]
+
+use std::num::NonZeroU32;
+
+use vvenc::*;
+
+#[test]
+fn serde_round_trip() {
+    let mut config = Config::default();
+    config
+        .set_width(1280)
+        .set_height(720)
+        .set_framerate(Rational { num: 50, den: 1 })
+        .set_qp(Qp::new(27).unwrap())
+        .set_level(Level::Level4_1)
+        .set_internal_chroma_format(ChromaFormat::Chroma420);
+
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["width"], 1280);
+    assert_eq!(
+        json["framerate"],
+        serde_json::json!({ "num": 50, "den": 1 })
+    );
+    assert_eq!(json["qp"], 27);
+    assert_eq!(json["level"], "level4_1");
+    assert!(json.get("gop_size").is_none());
+
+    let parsed: Config = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.width(), 1280);
+    assert_eq!(parsed.height(), 720);
+    assert_eq!(parsed.framerate(), Rational { num: 50, den: 1 });
+    assert_eq!(parsed.qp(), Qp::new(27).unwrap());
+    assert_eq!(parsed.level(), Level::Level4_1);
+    assert_eq!(parsed.gop_size(), Config::default().gop_size());
+}
+
+#[test]
+fn serde_rejects_invalid() {
+    assert!(
+        serde_json::from_str::<Config>(r#"{ "width": 1280, "height": 720, "qp": 64 }"#).is_err()
+    );
+    assert!(serde_json::from_str::<Config>(r#"{ "width": -1, "height": 720 }"#).is_err());
+    assert!(serde_json::from_str::<Config>(r#"{ "width": 1280, "unknown": 1 }"#).is_err());
+}
+
+#[test]
+fn serde_round_trip_fields() {
+    let mut config = Config::default();
+    config
+        .set_width(1280)
+        .set_height(720)
+        .set_hdr_mode(HdrMode::UserDefined)
+        .set_mastering_display(Some(
+            "G(13250,34500)B(7500,3000)R(34000,16000)WP(15635,16450)L(10000000,1)"
+                .parse()
+                .unwrap(),
+        ))
+        .unwrap()
+        .set_content_light_level(Some("1000,400".parse().unwrap()))
+        .unwrap()
+        .set_rate_control(RateControl::CappedVbr {
+            target_bitrate: NonZeroU32::new(2_000_000).unwrap(),
+            max_bitrate: NonZeroU32::new(3_000_000).unwrap(),
+            passes: Passes::One,
+            initial_qp: Some(Qp::new(30).unwrap()),
+        })
+        .unwrap()
+        .set_lookahead(Some(false))
+        .set_mctf_speed(3)
+        .set_param("SearchRange", "128")
+        .unwrap();
+
+    let json = serde_json::to_value(&config).unwrap();
+    assert!(json.get("mastering_display").is_some());
+    assert!(json.get("rate_control").is_some());
+    assert_eq!(json["fields"]["m_LookAhead"], 0);
+    assert_eq!(json["fields"]["m_vvencMCTF.MCTFSpeed"], 3);
+    assert_eq!(json["fields"]["m_SearchRange"], 128);
+
+    let parsed: Config = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(parsed.mastering_display(), config.mastering_display());
+    assert_eq!(parsed.content_light_level(), config.content_light_level());
+    assert_eq!(parsed.rate_control(), config.rate_control());
+    assert_eq!(parsed.lookahead(), Some(false));
+    assert_eq!(parsed.mctf_speed(), 3);
+    assert_eq!(parsed.search_range(), 128);
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+
+    assert!(serde_json::from_value::<Config>(
+        serde_json::json!({ "fields": { "m_NoSuchMember": 1 } })
+    )
+    .is_err());
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "async")]
+
+use futures::{executor::LocalPool, task::LocalSpawnExt, SinkExt, StreamExt};
+use vvenc::*;
+
+mod common;
+
+use common::{config, CHROMA_FORMAT, HEIGHT, WIDTH};
+
+#[test]
+fn stream() {
+    const NUM_FRAMES: u64 = 8;
+
+    let mut pool = LocalPool::new();
+    let (mut sink, stream) = pool.run_until(encoder_stream::<u64>(config(), 2)).unwrap();
+
+    pool.spawner()
+        .spawn_local(async move {
+            for cts in 0..NUM_FRAMES {
+                let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+                buffer.set_cts(cts);
+                buffer.set_opaque(cts);
+                sink.send(buffer).await.unwrap();
+            }
+            sink.close().await.unwrap();
+        })
+        .unwrap();
+
+    let packets: Vec<_> = pool.run_until(stream.collect());
+    assert_eq!(packets.len(), NUM_FRAMES as usize);
+    for packet in packets {
+        let mut packet = packet.unwrap();
+        assert_eq!(packet.cts().unwrap(), *packet.take_opaque().unwrap());
+    }
+}
+
+#[test]
+fn stream_drop() {
+    let mut pool = LocalPool::new();
+    let (mut sink, stream) = pool.run_until(encoder_stream::<()>(config(), 2)).unwrap();
+    drop(stream);
+
+    // The sink outlives the stream, but the worker must not keep waiting for its frames.
+    let buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+    assert_eq!(pool.run_until(sink.send(buffer)), Err(Error::Disconnected));
+}
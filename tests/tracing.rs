@@ -0,0 +1,78 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use vvenc::*;
+
+mod common;
+
+use common::{config, CHROMA_FORMAT, HEIGHT, WIDTH};
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn tracing_span() {
+    let capture = Capture::default();
+    let writer = capture.clone();
+    // Global, since VVenC logs from its own threads.
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .init();
+
+    let mut config = config();
+    config
+        .set_log_level(LogLevel::Details)
+        .with_tracing(tracing::info_span!(
+            "encoder",
+            job_id = 7,
+            rendition = "160p"
+        ));
+
+    let mut encoder = Encoder::<()>::with_config(config).unwrap();
+    assert!(encoder.config().tracing_span().is_some());
+    let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+    buffer.set_cts(0);
+    encoder.encode_packet(&mut buffer).unwrap();
+    for packet in encoder.drain() {
+        packet.unwrap();
+    }
+    drop(encoder);
+
+    let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<_> = output.lines().collect();
+    assert!(lines
+        .iter()
+        .all(|line| line.contains("encoder{job_id=7 rendition=\"160p\"}")));
+    assert!(lines
+        .iter()
+        .any(|line| line.contains(" encode elapsed_us=")));
+    assert!(lines.iter().any(|line| line.contains(" flush elapsed_us=")));
+    // VVenC messages, not just timing events.
+    assert!(lines.iter().any(|line| !line.contains("elapsed_us=")));
+}
+
+#[test]
+fn tracing_span_replaced_logger() {
+    let mut config = Config::default();
+    config.with_tracing(tracing::info_span!("encoder"));
+    assert!(config.tracing_span().is_some());
+
+    config.set_logger(|_: LogLevel, _: &str| {});
+    assert!(config.tracing_span().is_none());
+}
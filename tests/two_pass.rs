@@ -0,0 +1,47 @@
+use vvenc::*;
+
+mod common;
+
+use common::{config, CHROMA_FORMAT, HEIGHT, WIDTH};
+
+#[test]
+fn two_pass() {
+    const NUM_FRAMES: u64 = 8;
+
+    let mut config = config();
+    config.set_target_bitrate(100_000);
+
+    let mut encoder = TwoPassEncoder::with_config(config).unwrap();
+    let stats_file = encoder.stats_file().to_path_buf();
+    assert!(stats_file.exists());
+
+    let frames = || {
+        (0..NUM_FRAMES).map(|cts| {
+            let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+            buffer
+                .plane_mut(YUVComponent::Y)
+                .data_mut()
+                .fill((cts * 64) as i16);
+            buffer.set_cts(cts);
+            buffer.set_opaque(cts);
+            buffer
+        })
+    };
+
+    let packets: Vec<_> = encoder
+        .encode(frames)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(packets.len(), NUM_FRAMES as usize);
+    for mut packet in packets {
+        assert!(!packet.payload().is_empty());
+        assert_eq!(packet.cts().unwrap(), *packet.take_opaque().unwrap());
+    }
+
+    assert_eq!(encoder.encode(frames).err(), Some(Error::Finished));
+
+    drop(encoder);
+    assert!(!stats_file.exists());
+}
@@ -0,0 +1,84 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use vvenc::*;
+
+mod common;
+
+use common::{config, CHROMA_FORMAT, HEIGHT, WIDTH};
+
+#[test]
+fn worker() {
+    const NUM_FRAMES: u64 = 8;
+
+    let mut worker = EncoderWorker::spawn(config(), 2).unwrap();
+
+    for cts in 0..NUM_FRAMES {
+        let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+        buffer.set_cts(cts);
+        buffer.set_opaque(cts);
+        worker.send(buffer).unwrap();
+    }
+    worker.flush();
+    assert_eq!(
+        worker
+            .send(YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT))
+            .unwrap_err(),
+        Error::Finished
+    );
+
+    let mut num_packets = 0;
+    while let Some(packet) = worker.recv() {
+        let mut packet = packet.unwrap();
+        assert_eq!(packet.cts().unwrap(), *packet.take_opaque().unwrap());
+        num_packets += 1;
+    }
+    assert_eq!(num_packets, NUM_FRAMES);
+}
+
+#[test]
+fn worker_invalid_config() {
+    let mut config = config();
+    config.set_width(-1);
+    assert!(EncoderWorker::<()>::spawn(config, 1).is_err());
+}
+
+#[test]
+fn worker_cancel() {
+    const NUM_FRAMES: u64 = 32;
+
+    // VVenC reports every picture it has encoded.
+    let encoded = Arc::new(AtomicUsize::new(0));
+    let mut config = config();
+    config.set_log_level(LogLevel::Details).set_logger({
+        let encoded = encoded.clone();
+        move |_: LogLevel, message: &str| {
+            if message.trim_start().starts_with("POC") {
+                encoded.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    });
+
+    let mut worker = EncoderWorker::spawn(config, NUM_FRAMES as usize).unwrap();
+    for cts in 0..NUM_FRAMES {
+        let mut buffer = YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT);
+        buffer.set_cts(cts);
+        buffer.set_opaque(cts);
+        worker.send(buffer).unwrap();
+    }
+    worker.cancel();
+
+    // Queued frames are discarded rather than flushed through the encoder, and nothing encoded
+    // before the cancellation is delivered afterwards.
+    assert!(encoded.load(Ordering::Relaxed) < NUM_FRAMES as usize);
+    assert!(worker.recv().is_none());
+    assert!(worker.try_recv().is_none());
+    assert_eq!(
+        worker
+            .send(YUVBuffer::new(WIDTH, HEIGHT, CHROMA_FORMAT))
+            .unwrap_err(),
+        Error::Finished
+    );
+}
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+mod config_fields;
+
 const VVENC_VERSION: &str = "1.13.0";
 
 #[cfg(feature = "vendored")]
@@ -20,6 +22,9 @@ mod vendored {
 
 fn main() {
     println!("cargo:rerun-if-changed=wrapper.h");
+    println!("cargo:rerun-if-changed=shim.h");
+    println!("cargo:rerun-if-changed=shim.c");
+    println!("cargo:rerun-if-changed=config_fields.rs");
 
     #[cfg(feature = "vendored")]
     vendored::build_from_src();
@@ -29,6 +34,8 @@ fn main() {
         .probe("libvvenc")
         .expect("libvvenc not found in the system. To allow building it from source, use the \"vendored\" feature");
 
+    cc::Build::new().file("shim.c").compile("vvenc_rs_shim");
+
     let bindings = bindgen::Builder::default()
         .header("wrapper.h")
         .clang_args(
@@ -42,6 +49,9 @@ fn main() {
         .allowlist_type("ErrorCodes")
         .allowlist_function("vvenc_.*")
         .allowlist_var("VVENC.*")
+        .derive_partialeq(true)
+        // Keeps the trailing `//` comments of vvenc.h as docs of the config fields.
+        .clang_arg("-fparse-all-comments")
         .generate()
         .expect("Unable to generate bindings");
 
@@ -49,4 +59,52 @@ fn main() {
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
+
+    let bindings = bindings.to_string();
+    // Parsed here so that the safe wrapper compares numbers rather than parsing at runtime.
+    let version = header_version(&bindings).unwrap_or(library.version.as_str());
+    let [major, minor, patch] = parse_version(version);
+    std::fs::write(
+        out_path.join("vvenc_version.rs"),
+        format!(
+            "/// Version of the libvvenc headers the bindings were generated from.\n\
+             pub const VVENC_LINKED_VERSION_MAJOR: u32 = {major};\n\
+             pub const VVENC_LINKED_VERSION_MINOR: u32 = {minor};\n\
+             pub const VVENC_LINKED_VERSION_PATCH: u32 = {patch};\n"
+        ),
+    )
+    .expect("Couldn't write libvvenc version!");
+
+    // Description of every member, from which the safe wrapper generates its accessors.
+    let table = out_path.join("vvenc_config_fields.tsv");
+    std::fs::write(
+        &table,
+        config_fields::table(&config_fields::parse(&bindings)),
+    )
+    .expect("Couldn't write vvenc_config fields!");
+    println!("cargo:config_fields={}", table.display());
+}
+
+// The `VVENC_VERSION` string macro of vvenc/version.h, e.g. `1.13.0`.
+fn header_version(bindings: &str) -> Option<&str> {
+    let definition = bindings.split("pub const VVENC_VERSION:").nth(1)?;
+    let (_, literal) = definition.split_once('"')?;
+    let (version, _) = literal.split_once('"')?;
+    Some(version.trim_end_matches("\\0"))
+}
+
+// Accepts a missing patch number and ignores suffixes such as "-rc1".
+fn parse_version(version: &str) -> [u32; 3] {
+    let invalid = || panic!("invalid libvvenc version {version:?}");
+    let core = version
+        .trim()
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .unwrap_or_default();
+    let mut numbers = core
+        .split('.')
+        .map(|number| number.parse().unwrap_or_else(|_| invalid()));
+    let major = numbers.next().unwrap_or_else(invalid);
+    let minor = numbers.next().unwrap_or_else(invalid);
+    [major, minor, numbers.next().unwrap_or(0)]
 }
@@ -0,0 +1,270 @@
+// Describes the members of the generated `vvenc_config` struct for the safe wrapper, which
+// generates its accessors from it.
+//
+// The bindings are scanned as text: struct bodies are split on top-level commas, keeping string
+// literals (doc attributes) and nested brackets intact, so that both formatted and unformatted
+// bindgen output are understood.
+
+use std::collections::HashMap;
+
+pub struct Field {
+    // Member path, nested structs being flattened as `m_outer.inner`.
+    pub path: String,
+    // Type as declared, e.g. `vvencCostMode`.
+    pub declared: String,
+    // Type with type aliases resolved, e.g. `::std::os::raw::c_uint` or `[bool;3]`.
+    pub ty: String,
+    pub doc: String,
+}
+
+pub fn parse(bindings: &str) -> Vec<Field> {
+    let structs = structs(bindings);
+    let aliases = aliases(bindings);
+    let mut fields = Vec::new();
+    flatten(&structs, &aliases, "vvenc_config", "", &mut fields);
+    assert!(!fields.is_empty(), "vvenc_config not found in bindings");
+    fields
+}
+
+// One member per line: tab-separated path, declared type, resolved type and doc, the doc having
+// its backslashes, tabs and newlines escaped.
+pub fn table(fields: &[Field]) -> String {
+    let mut table = String::new();
+    for field in fields {
+        let doc = field
+            .doc
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n");
+        table.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            field.path, field.declared, field.ty, doc
+        ));
+    }
+    table
+}
+
+struct Member {
+    name: String,
+    ty: String,
+    doc: String,
+}
+
+fn flatten(
+    structs: &HashMap<String, Vec<Member>>,
+    aliases: &HashMap<String, String>,
+    name: &str,
+    prefix: &str,
+    fields: &mut Vec<Field>,
+) {
+    let Some(members) = structs.get(name) else {
+        return;
+    };
+    for member in members {
+        let ty = resolve(&member.ty, aliases);
+        let path = format!("{prefix}{}", member.name);
+        // Nested structs are flattened, their members being plain values like the top-level ones.
+        if structs.contains_key(&ty) && prefix.matches('.').count() < 3 {
+            flatten(structs, aliases, &ty, &format!("{path}."), fields);
+        } else {
+            fields.push(Field {
+                path,
+                declared: member.ty.clone(),
+                ty,
+                doc: member.doc.clone(),
+            });
+        }
+    }
+}
+
+fn resolve(ty: &str, aliases: &HashMap<String, String>) -> String {
+    if let Some((element, len)) = array(ty) {
+        return format!(
+            "[{};{}]",
+            resolve(element, aliases),
+            len.trim_end_matches("usize")
+        );
+    }
+    match aliases.get(ty) {
+        Some(target) if target != ty => resolve(target, aliases),
+        _ => ty.to_owned(),
+    }
+}
+
+pub fn array(ty: &str) -> Option<(&str, &str)> {
+    let inner = ty.strip_prefix('[')?.strip_suffix(']')?;
+    let split = split_top_level(inner, ';');
+    match split.as_slice() {
+        [element, len] => Some((element.trim(), len.trim())),
+        _ => None,
+    }
+}
+
+fn structs(bindings: &str) -> HashMap<String, Vec<Member>> {
+    let mut structs = HashMap::new();
+    let mut rest = bindings;
+    while let Some(start) = rest.find("pub struct ") {
+        rest = &rest[start + "pub struct ".len()..];
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        let after_name = rest[name_len..].trim_start();
+        let Some(body) = after_name.strip_prefix('{') else {
+            continue;
+        };
+        let Some(end) = closing(body, '{', '}') else {
+            break;
+        };
+        let members = split_top_level(&body[..end], ',')
+            .into_iter()
+            .filter_map(member)
+            .collect();
+        structs.insert(name.to_owned(), members);
+        rest = &body[end..];
+    }
+    structs
+}
+
+fn aliases(bindings: &str) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    let mut rest = bindings;
+    while let Some(start) = rest.find("pub type ") {
+        rest = &rest[start + "pub type ".len()..];
+        let Some((name, definition)) = rest.split_once('=') else {
+            break;
+        };
+        let Some(ty) = split_top_level(definition, ';').into_iter().next() else {
+            break;
+        };
+        aliases.insert(name.trim().to_owned(), normalize(ty));
+    }
+    aliases
+}
+
+fn member(text: &str) -> Option<Member> {
+    let mut text = text.trim();
+    let mut doc = Vec::new();
+    while let Some(attribute) = text.strip_prefix("#") {
+        let attribute = attribute.trim_start().strip_prefix('[')?;
+        let end = closing(attribute, '[', ']')?;
+        let (content, value) = attribute[..end].split_once('=').unwrap_or_default();
+        if content.trim() == "doc" {
+            doc.push(string_literal(value.trim())?);
+        }
+        text = attribute[end + 1..].trim_start();
+    }
+    let text = text
+        .strip_prefix("pub(crate)")
+        .or_else(|| text.strip_prefix("pub"))
+        .unwrap_or(text);
+    let (name, ty) = text.split_once(':')?;
+    let doc: Vec<_> = doc.iter().map(|line| line.trim()).collect();
+    Some(Member {
+        name: name.trim().to_owned(),
+        ty: normalize(ty),
+        doc: doc.join("\n").trim().to_owned(),
+    })
+}
+
+// Collapses whitespace, keeping it only between two identifiers.
+fn normalize(ty: &str) -> String {
+    let words: Vec<_> = ty.split_whitespace().collect();
+    let mut normalized = String::new();
+    for word in words {
+        let separate = normalized.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+            && word.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        if separate {
+            normalized.push(' ');
+        }
+        normalized.push_str(word);
+    }
+    normalized
+}
+
+fn string_literal(text: &str) -> Option<String> {
+    let mut chars = text.strip_prefix('"')?.chars();
+    let mut string = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(string),
+            '\\' => match chars.next()? {
+                'n' => string.push('\n'),
+                't' => string.push('\t'),
+                'r' => string.push('\r'),
+                '0' => string.push('\0'),
+                'u' => {
+                    let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                    string.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                }
+                c => string.push(c),
+            },
+            c => string.push(c),
+        }
+    }
+}
+
+// Position of the bracket closing the one opened just before `text`.
+fn closing(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+// Splits on `separator` outside of string literals and brackets.
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut previous = ' ';
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '(' | '[' | '{' | '<' => depth += 1,
+                // `->` in function pointer types does not close a bracket.
+                '>' if previous == '-' => {}
+                ')' | ']' | '}' | '>' => depth -= 1,
+                _ if c == separator && depth == 0 => {
+                    parts.push(&text[start..i]);
+                    start = i + c.len_utf8();
+                }
+                _ => {}
+            }
+        }
+        previous = c;
+    }
+    if !text[start..].trim().is_empty() {
+        parts.push(&text[start..]);
+    }
+    parts
+}
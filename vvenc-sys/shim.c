@@ -0,0 +1,28 @@
+#include "shim.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+
+void vvenc_rs_log_callback(void *ctx, int level, const char *fmt, va_list args) {
+  const vvencRsLogContext *context = ctx;
+  char buffer[1024];
+  va_list retry;
+
+  va_copy(retry, args);
+  int len = vsnprintf(buffer, sizeof(buffer), fmt, args);
+  if (len < 0) {
+    context->callback(context, level, fmt);
+  } else if ((size_t)len < sizeof(buffer)) {
+    context->callback(context, level, buffer);
+  } else {
+    char *message = malloc((size_t)len + 1);
+    if (message != NULL && vsnprintf(message, (size_t)len + 1, fmt, retry) >= 0) {
+      context->callback(context, level, message);
+    } else {
+      /* Truncated rather than dropped. */
+      context->callback(context, level, buffer);
+    }
+    free(message);
+  }
+  va_end(retry);
+}
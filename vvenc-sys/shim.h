@@ -0,0 +1,22 @@
+#pragma once
+
+#include <stdarg.h>
+
+struct vvencRsLogContext;
+
+/* Receives a message already formatted by vvenc_rs_log_callback. */
+typedef void (*vvencRsMessageCallback)(const struct vvencRsLogContext *ctx, int level,
+                                       const char *message);
+
+/* Logging context handed to VVenC along with vvenc_rs_log_callback. */
+typedef struct vvencRsLogContext {
+  vvencRsMessageCallback callback;
+} vvencRsLogContext;
+
+/*
+ * vvencLoggingCallback formatting the message with vsnprintf, so that callers do not need to
+ * handle the target-specific va_list. `ctx` must point to a vvencRsLogContext.
+ *
+ * If formatting fails, the format string itself is forwarded.
+ */
+void vvenc_rs_log_callback(void *ctx, int level, const char *fmt, va_list args);
@@ -1,5 +1,8 @@
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
+// The derived `PartialEq` of `vvenc_config` compares its callback pointer.
+#![allow(unknown_lints, unpredictable_function_pointer_comparisons)]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+include!(concat!(env!("OUT_DIR"), "/vvenc_version.rs"));
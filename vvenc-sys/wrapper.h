@@ -1 +1,2 @@
-#include <vvenc/vvenc.h>
\ No newline at end of file
+#include <vvenc/vvenc.h>
+#include "shim.h"